@@ -0,0 +1,147 @@
+use crate::extract::{self, SourceKind};
+
+/// Comment markers that declare keys used in ways the scan cannot see
+const MARKERS: &[&str] = &["hunt-keep:", "i18n-keys:"];
+
+/// A key or keep-list pattern named by an annotation, with its byte offset in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Annotation {
+    pub pattern: String,
+    pub offset: usize,
+}
+
+/// Find annotations such as `// hunt-keep: status.*` or `/* i18n-keys: errors.network errors.timeout */`.
+/// Patterns are separated by spaces or commas and run to the end of the line, or to the end of the
+/// block comment the marker is in. With a source kind only markers inside comments count; plain
+/// text files have no comment syntax to go by, so markers count anywhere
+pub fn find_annotations(source: &str, kind: Option<SourceKind>) -> Vec<Annotation> {
+    let comments = kind.map(|kind| extract::comment_ranges(source, kind));
+    let mut annotations = Vec::new();
+
+    for marker in MARKERS {
+        for (marker_at, _) in source.match_indices(marker) {
+            let comment = match &comments {
+                Some(comments) => {
+                    match comments
+                        .iter()
+                        .find(|(from, to)| *from <= marker_at && marker_at < *to)
+                    {
+                        Some(&comment) => Some(comment),
+                        // In a string or in markup, not an annotation
+                        None => continue,
+                    }
+                }
+                None => None,
+            };
+
+            let start = marker_at + marker.len();
+            // Inside a block comment, the list runs to its end and may span lines
+            let in_block = match comment {
+                Some((from, _)) => !source[from..].starts_with("//"),
+                None => {
+                    let before = &source[..marker_at];
+                    before
+                        .rfind("/*")
+                        .is_some_and(|open| !before[open..].contains("*/"))
+                }
+            };
+            let end = match comment {
+                Some((_, to)) if in_block => to,
+                _ => match source[start..].find(if in_block { "*/" } else { "\n" }) {
+                    Some(at) => start + at,
+                    None => source.len(),
+                },
+            };
+
+            let list = &source[start..end];
+            let list = list.split("*/").next().unwrap_or(list);
+            let list = list.split("-->").next().unwrap_or(list);
+            for (at, pattern) in split_patterns(list) {
+                // Leading `*` of JSDoc-style continuation lines
+                if pattern == "*" && in_block {
+                    continue;
+                }
+                annotations.push(Annotation {
+                    pattern: pattern.to_string(),
+                    offset: start + at,
+                });
+            }
+        }
+    }
+
+    annotations.sort_by_key(|annotation| annotation.offset);
+    annotations
+}
+
+/// Patterns of a list separated by whitespace or commas, with their offsets in the list
+fn split_patterns(list: &str) -> Vec<(usize, &str)> {
+    let mut patterns = Vec::new();
+    let mut start = None;
+
+    for (i, c) in list.char_indices() {
+        let separator = c.is_whitespace() || c == ',';
+        match (start, separator) {
+            (None, false) => start = Some(i),
+            (Some(from), true) => {
+                patterns.push((from, &list[from..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        patterns.push((from, &list[from..]));
+    }
+
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(source: &str) -> Vec<String> {
+        find_annotations(source, None)
+            .into_iter()
+            .map(|annotation| annotation.pattern)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_annotations() {
+        let source = "// hunt-keep: status.*\nconst a = 1; /* i18n-keys: errors.network, errors.timeout */\n";
+        assert_eq!(
+            patterns(source),
+            ["status.*", "errors.network", "errors.timeout"]
+        );
+        let annotations = find_annotations(source, None);
+        assert_eq!(&source[annotations[1].offset..][..14], "errors.network");
+
+        assert_eq!(
+            patterns("{/* hunt-keep: a.b */}\n<!-- i18n-keys: c.d -->\n# hunt-keep: e"),
+            ["a.b", "c.d", "e"]
+        );
+        assert_eq!(
+            patterns("/**\n * i18n-keys:\n *   plan.free\n *   plan.pro\n */\nt(x)"),
+            ["plan.free", "plan.pro"]
+        );
+    }
+
+    #[test]
+    fn test_annotations_only_in_comments() {
+        let source =
+            "const help = \"hunt-keep: a.*\";\n// hunt-keep: b.c\n/* i18n-keys:\n * d.e */\n";
+        let found: Vec<String> = find_annotations(source, Some(SourceKind::TypeScript))
+            .into_iter()
+            .map(|annotation| annotation.pattern)
+            .collect();
+        assert_eq!(found, ["b.c", "d.e"]);
+
+        let markup = "<p>hunt-keep: a.*</p>\n<!-- i18n-keys: b.c\n  d.e -->\n<script>\nconst s = 'i18n-keys: f';\n</script>\n";
+        let found: Vec<String> = find_annotations(markup, Some(SourceKind::Markup))
+            .into_iter()
+            .map(|annotation| annotation.pattern)
+            .collect();
+        assert_eq!(found, ["b.c", "d.e"]);
+    }
+}
@@ -0,0 +1,341 @@
+use crate::json_edit::PathSegment;
+use crate::translation::FileChange;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the archive file format
+const ARCHIVE_VERSION: u32 = 1;
+
+/// Directory archives are written to unless configured otherwise
+pub const DEFAULT_ARCHIVE_DIR: &str = ".hunt-archive";
+
+/// Keys removed by one `--clear` run, with everything needed to put them back
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Archive {
+    pub schema_version: u32,
+    /// UTC time of the cleanup (RFC 3339)
+    pub created_at: String,
+    pub keys: Vec<ArchivedKey>,
+}
+
+/// One removed key in one translation file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchivedKey {
+    pub key: String,
+    /// Translation file, relative to the directory of the archive file
+    pub file: String,
+    /// Nesting of the key with the position of every level in the original file
+    pub path: Vec<PathSegment>,
+    pub value: Value,
+}
+
+/// Outcome of restoring an archive
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RestoreSummary {
+    pub changes: Vec<FileChange>,
+    /// Keys put back, one per file they were removed from
+    pub restored: Vec<String>,
+    /// Keys skipped because the file has them again
+    pub already_present: Vec<String>,
+}
+
+/// Archive of every key removed by the planned changes, with absolute file paths until it is
+/// written
+pub fn archive_changes(changes: &[FileChange]) -> Archive {
+    let (created_at, _) = utc_timestamp(SystemTime::now());
+    Archive {
+        schema_version: ARCHIVE_VERSION,
+        created_at,
+        keys: changes
+            .iter()
+            .flat_map(|change| {
+                let file = fs::canonicalize(&change.path)
+                    .unwrap_or_else(|_| change.path.clone())
+                    .to_string_lossy()
+                    .to_string();
+                change.removed.iter().map(move |removed| ArchivedKey {
+                    key: removed.key.clone(),
+                    file: file.clone(),
+                    path: removed.segments.clone(),
+                    value: removed.value.clone(),
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Write an archive to a new timestamped file in `dir` and return its path
+pub fn write_archive(dir: &str, archive: &Archive) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create archive directory {}: {}", dir, e))?;
+
+    let (_, stamp) = utc_timestamp(SystemTime::now());
+    let mut path = Path::new(dir).join(format!("hunt-archive-{}.json", stamp));
+    let mut attempt = 1;
+    while path.exists() {
+        path = Path::new(dir).join(format!("hunt-archive-{}-{}.json", stamp, attempt));
+        attempt += 1;
+    }
+
+    // Relative to the archive so it keeps working from any directory, or when moved with the files
+    let base = fs::canonicalize(dir)?;
+    let keys = archive
+        .keys
+        .iter()
+        .map(|archived| ArchivedKey {
+            file: relative_path(&base, Path::new(&archived.file)),
+            ..archived.clone()
+        })
+        .collect();
+    let content = serde_json::to_string_pretty(&Archive {
+        schema_version: archive.schema_version,
+        created_at: archive.created_at.clone(),
+        keys,
+    })?;
+    fs::write(&path, format!("{}\n", content))
+        .map_err(|e| format!("Failed to write archive {}: {}", path.display(), e))?;
+
+    Ok(path)
+}
+
+/// Read an archive written by `--clear`, with file paths resolved against its directory
+pub fn load_archive(path: &str) -> Result<Archive, Box<dyn std::error::Error>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read archive {}: {}", path, e))?;
+    let mut archive: Archive = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse archive {}: {}", path, e))?;
+
+    if archive.schema_version != ARCHIVE_VERSION {
+        return Err(format!(
+            "Unsupported archive version {} in {} (expected {})",
+            archive.schema_version, path, ARCHIVE_VERSION
+        )
+        .into());
+    }
+
+    let base = Path::new(path).parent().unwrap_or(Path::new(""));
+    for archived in &mut archive.keys {
+        archived.file = base.join(&archived.file).to_string_lossy().to_string();
+    }
+
+    Ok(archive)
+}
+
+/// Compute the files with the archived keys put back at their original positions, without writing
+/// anything. With `patterns` (keep-list syntax), only matching keys are restored. Translation files
+/// that no longer exist are an error unless `create` is set
+pub fn plan_restore(
+    archive: &Archive,
+    patterns: &[String],
+    create: bool,
+) -> Result<RestoreSummary, Box<dyn std::error::Error>> {
+    let mut summary = RestoreSummary::default();
+    let selection = crate::keep::KeepList::new(patterns)?;
+
+    // Files in archive order; keys within a file stay in document order so indices line up
+    let mut files: Vec<&str> = Vec::new();
+    for archived in &archive.keys {
+        if !files.contains(&archived.file.as_str()) {
+            files.push(&archived.file);
+        }
+    }
+
+    for file in files {
+        let path = PathBuf::from(file);
+        let original = if path.is_file() {
+            fs::read_to_string(&path)?
+        } else if create {
+            "{\n}\n".to_string()
+        } else {
+            return Err(format!(
+                "Translation file {} does not exist (use --create to restore into a new file)",
+                file
+            )
+            .into());
+        };
+
+        let mut updated = original.clone();
+        let keys = archive.keys.iter().filter(|archived| {
+            archived.file == file
+                && (patterns.is_empty() || selection.matching(&archived.key).is_some())
+        });
+        for archived in keys {
+            let root = crate::json_spans::parse(&updated)
+                .map_err(|e| format!("Failed to parse {}: {}", file, e))?;
+            match crate::json_edit::insert_entry(&updated, &root, &archived.path, &archived.value)
+                .map_err(|e| format!("Cannot restore '{}' in {}: {}", archived.key, file, e))?
+            {
+                Some(text) => {
+                    updated = text;
+                    summary.restored.push(archived.key.clone());
+                }
+                None => summary.already_present.push(archived.key.clone()),
+            }
+        }
+
+        if updated != original {
+            summary.changes.push(FileChange {
+                path,
+                original,
+                updated,
+                removed: Vec::new(),
+                kept: Vec::new(),
+            });
+        }
+    }
+
+    Ok(summary)
+}
+
+/// `path` relative to the directory `base` (with forward slashes); absolute when they share no root
+fn relative_path(base: &Path, path: &Path) -> String {
+    let base: Vec<_> = base.components().collect();
+    let path: Vec<_> = path.components().collect();
+    let common = base.iter().zip(&path).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return path
+            .iter()
+            .collect::<PathBuf>()
+            .to_string_lossy()
+            .to_string();
+    }
+
+    std::iter::repeat_n("..".into(), base.len() - common)
+        .chain(
+            path[common..]
+                .iter()
+                .map(|component| component.as_os_str().to_string_lossy()),
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// RFC 3339 and file-name-friendly (`20261017T093000Z`) forms of a UTC time
+fn utc_timestamp(time: SystemTime) -> (String, String) {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let (hour, minute, second) = (seconds % 86_400 / 3600, seconds % 3600 / 60, seconds % 60);
+
+    (
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year, month, day, hour, minute, second
+        ),
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+            year, month, day, hour, minute, second
+        ),
+    )
+}
+
+/// Gregorian date of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn test_archive_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("en.json");
+        let original = "{\n  \"app\": {\n    \"title\": \"T\",\n    \"old\": \"O\"\n  },\n  \"status\": {\n    \"gone\": \"G\"\n  }\n}\n";
+        fs::write(&file, original).unwrap();
+
+        let unused = vec!["app.old".to_string(), "status.gone".to_string()];
+        let options = crate::translation::CleanupOptions {
+            namespaced: false,
+            prune_empty: true,
+        };
+        let changes = crate::translation::plan_unused_key_removal(
+            &file.to_string_lossy(),
+            &unused,
+            &HashSet::new(),
+            options,
+        )
+        .unwrap();
+        crate::translation::apply_changes(&changes).unwrap();
+
+        let archive_dir = root.join("archive");
+        let written =
+            write_archive(&archive_dir.to_string_lossy(), &archive_changes(&changes)).unwrap();
+        let content = fs::read_to_string(&written).unwrap();
+        assert!(content.contains("\"file\": \"../en.json\""));
+        let archive = load_archive(&written.to_string_lossy()).unwrap();
+        assert_eq!(archive.keys.len(), 2);
+        assert_eq!(archive.keys[1].value, Value::from("G"));
+
+        // Partial restore first, then the rest
+        let summary = plan_restore(&archive, &["status.*".to_string()], false).unwrap();
+        assert_eq!(summary.restored, ["status.gone"]);
+        crate::translation::apply_changes(&summary.changes).unwrap();
+
+        let summary = plan_restore(&archive, &[], false).unwrap();
+        assert_eq!(summary.restored, ["app.old"]);
+        assert_eq!(summary.already_present, ["status.gone"]);
+        crate::translation::apply_changes(&summary.changes).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+
+        // Deleted files are only recreated on request
+        fs::remove_file(&file).unwrap();
+        let error = plan_restore(&archive, &[], false).unwrap_err();
+        assert!(error.to_string().contains("does not exist"));
+        let summary = plan_restore(&archive, &[], true).unwrap();
+        crate::translation::apply_changes(&summary.changes).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "{\n  \"app\": {\n    \"old\": \"O\"\n  },\n  \"status\": {\n    \"gone\": \"G\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/repo/.hunt-archive");
+        assert_eq!(
+            relative_path(base, Path::new("/repo/locales/en.json")),
+            "../locales/en.json"
+        );
+        assert_eq!(
+            relative_path(base, Path::new("/repo/.hunt-archive/en.json")),
+            "en.json"
+        );
+    }
+
+    #[test]
+    fn test_utc_timestamp() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_792_229_400);
+        assert_eq!(
+            utc_timestamp(time),
+            (
+                "2026-10-17T09:30:00Z".to_string(),
+                "20261017T093000Z".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+/// Version of the baseline file format
+const BASELINE_VERSION: u32 = 1;
+
+/// Snapshot of accepted unused keys; validation only fails for keys not in it
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Baseline {
+    pub schema_version: u32,
+    pub unused_keys: BTreeSet<String>,
+}
+
+/// Current unused keys compared with a baseline
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BaselineComparison {
+    /// Unused keys not in the baseline
+    pub new_keys: Vec<String>,
+    /// Unused keys accepted by the baseline
+    pub baselined_keys: Vec<String>,
+    /// Baseline entries that are no longer unused
+    pub now_used: Vec<String>,
+    /// Baseline entries that no longer exist in the translations
+    pub deleted: Vec<String>,
+}
+
+impl Baseline {
+    /// Compare the current unused keys against the baseline. All lists are sorted
+    pub fn compare(
+        &self,
+        unused_keys: &[String],
+        translations: &HashMap<String, Value>,
+    ) -> BaselineComparison {
+        let mut comparison = BaselineComparison::default();
+
+        for key in unused_keys {
+            if self.unused_keys.contains(key) {
+                comparison.baselined_keys.push(key.clone());
+            } else {
+                comparison.new_keys.push(key.clone());
+            }
+        }
+        comparison.new_keys.sort();
+        comparison.baselined_keys.sort();
+
+        let unused: BTreeSet<&String> = unused_keys.iter().collect();
+        for key in &self.unused_keys {
+            if !translations.contains_key(key) {
+                comparison.deleted.push(key.clone());
+            } else if !unused.contains(key) {
+                comparison.now_used.push(key.clone());
+            }
+        }
+
+        comparison
+    }
+}
+
+impl BaselineComparison {
+    /// Whether the baseline lists keys that are no longer unused
+    pub fn is_stale(&self) -> bool {
+        !self.now_used.is_empty() || !self.deleted.is_empty()
+    }
+}
+
+/// Read a baseline file written by `--write-baseline`
+pub fn load_baseline(path: &str) -> Result<Baseline, Box<dyn std::error::Error>> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read baseline {}: {}", path, e))?;
+    let baseline: Baseline = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse baseline {}: {}", path, e))?;
+
+    if baseline.schema_version != BASELINE_VERSION {
+        return Err(format!(
+            "Unsupported baseline version {} in {} (expected {})",
+            baseline.schema_version, path, BASELINE_VERSION
+        )
+        .into());
+    }
+
+    Ok(baseline)
+}
+
+/// Write the current unused keys as a baseline, sorted so the file diffs cleanly
+pub fn write_baseline(
+    path: &str,
+    unused_keys: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let baseline = Baseline {
+        schema_version: BASELINE_VERSION,
+        unused_keys: unused_keys.iter().cloned().collect(),
+    };
+
+    let content = serde_json::to_string_pretty(&baseline)?;
+    fs::write(path, format!("{}\n", content))
+        .map_err(|e| format!("Failed to write baseline {}: {}", path, e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let baseline = Baseline {
+            schema_version: BASELINE_VERSION,
+            unused_keys: ["legacy.a", "legacy.b", "legacy.gone"]
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+        };
+        let translations = HashMap::from([
+            ("legacy.a".to_string(), json!("A")),
+            ("legacy.b".to_string(), json!("B")),
+            ("fresh".to_string(), json!("F")),
+        ]);
+        let unused = vec!["fresh".to_string(), "legacy.a".to_string()];
+
+        let comparison = baseline.compare(&unused, &translations);
+
+        assert_eq!(comparison.new_keys, ["fresh"]);
+        assert_eq!(comparison.baselined_keys, ["legacy.a"]);
+        assert_eq!(comparison.now_used, ["legacy.b"]);
+        assert_eq!(comparison.deleted, ["legacy.gone"]);
+        assert!(comparison.is_stale());
+    }
+
+    #[test]
+    fn test_write_and_load_baseline() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("baseline.json");
+        let path = path.to_string_lossy().to_string();
+
+        write_baseline(&path, &["b".to_string(), "a".to_string()]).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.find("\"a\"").unwrap() < content.find("\"b\"").unwrap());
+
+        let baseline = load_baseline(&path).unwrap();
+        assert_eq!(baseline.unused_keys.len(), 2);
+    }
+}
@@ -1,39 +1,462 @@
-use clap::Parser;
+use crate::config::Config;
+use crate::extract::{CallSites, Extraction};
+use crate::ignore::IgnoreRules;
+use crate::keep::KeepList;
+use crate::output::Format;
+use crate::plural::{PluralFormat, Variants};
+use crate::search::SearchOptions;
+use crate::translation::{ArrayMode, CleanupOptions};
+use clap::{CommandFactory, Parser, Subcommand};
+use std::path::Path;
+
+/// i18next's default namespace
+const DEFAULT_NAMESPACE: &str = "translation";
 
 #[derive(Parser)]
 #[command(name = "hunt")]
 #[command(about = "A lion's hunt for dead translation keys in your codebase.")]
 #[command(version = "0.1.0")]
-#[command(arg_required_else_help = true)]
 pub struct Cli {
-    /// Path to the translation file (JSON) or directory containing JSON files
-    pub translation_path: String,
+    /// Path to the translation file (JSON) or directory containing JSON files.
+    /// Can be omitted when `translations` is set in the config file.
+    pub translation_path: Option<String>,
 
     /// Source directories to search (can specify multiple). If not provided, uses current directory.
-    #[arg(short = 'd', long = "dir")]
+    #[arg(short = 'd', long = "dir", global = true)]
     pub source_dirs: Vec<String>,
 
     /// Show statistics (files processed, time elapsed, etc.)
-    #[arg(short = 's', long = "stats")]
+    #[arg(short = 's', long = "stats", overrides_with = "no_stats")]
     pub show_stats: bool,
 
+    /// Do not show statistics, even if the config file turns them on
+    #[arg(long = "no-stats")]
+    pub no_stats: bool,
+
     /// Remove unused keys from translation files
     #[arg(short = 'c', long = "clear")]
     pub clear_unused: bool,
 
+    /// With --clear, keep objects and arrays whose keys were all removed instead of pruning them
+    #[arg(long = "keep-empty", requires = "clear_unused")]
+    pub keep_empty: bool,
+
+    /// With --clear, pick which unused keys to remove: walk through them grouped by prefix and namespace
+    /// and keep, delete or add them to the config keep list
+    #[arg(short = 'i', long = "interactive", requires = "clear_unused")]
+    pub interactive: bool,
+
+    /// Directory --clear writes the archive of removed keys to (defaults to .hunt-archive)
+    #[arg(long = "archive-dir", value_name = "DIR", requires = "clear_unused")]
+    pub archive_dir: Option<String>,
+
+    /// With --clear, do not archive the removed keys
+    #[arg(
+        long = "no-archive",
+        requires = "clear_unused",
+        conflicts_with = "archive_dir"
+    )]
+    pub no_archive: bool,
+
+    /// With --clear, show a diff of the changes instead of writing the translation files
+    #[arg(long = "dry-run", requires = "clear_unused")]
+    pub dry_run: bool,
+
+    /// With --dry-run, write the changes to a patch file (apply it with `git apply`)
+    #[arg(long = "patch", value_name = "FILE", requires = "dry_run")]
+    pub patch: Option<String>,
+
     /// Validate that there are no unused keys (exits with code 1 if unused keys found)
     /// Useful for pre-commit hooks
     #[arg(long = "validate")]
     pub validate: bool,
 
+    /// Report keys used in code (literal t() arguments) that are missing from the translations,
+    /// instead of unused keys. Combine with --validate to fail CI when any are found
+    #[arg(long = "missing", conflicts_with = "clear_unused")]
+    pub missing: bool,
+
+    /// Baseline file of accepted unused keys: only keys not in it are reported and fail --validate
+    #[arg(long = "baseline", value_name = "FILE", conflicts_with = "missing")]
+    pub baseline: Option<String>,
+
+    /// Write the current unused keys to a baseline file
+    #[arg(
+        long = "write-baseline",
+        value_name = "FILE",
+        conflicts_with = "missing"
+    )]
+    pub write_baseline: Option<String>,
+
     /// Show the list of unused keys
-    #[arg(long = "keys")]
+    #[arg(long = "keys", overrides_with = "no_keys")]
     pub show_keys: bool,
+
+    /// Do not show the list of unused keys, even if the config file turns it on
+    #[arg(long = "no-keys")]
+    pub no_keys: bool,
+
+    /// Also list the used keys with every file, line and column they are used at
+    #[arg(long = "with-locations", conflicts_with = "missing")]
+    pub with_locations: bool,
+
+    /// Report format: `human` (colored text), `json` (versioned schema for scripts and CI)
+    /// or `sarif` (SARIF 2.1.0 for code scanning)
+    #[arg(long = "format", value_name = "human|json|sarif", global = true)]
+    pub format: Option<String>,
+
+    /// Path to a config file. By default hunt.toml or .huntrc is looked up from the current directory upwards
+    #[arg(long = "config", conflicts_with = "no_config", global = true)]
+    pub config_path: Option<String>,
+
+    /// Do not load any config file
+    #[arg(long = "no-config", global = true)]
+    pub no_config: bool,
+
+    /// Gitignore-style glob of paths to skip (can specify multiple). Prefix with `!` to re-include
+    #[arg(long = "exclude", value_name = "GLOB", global = true)]
+    pub exclude: Vec<String>,
+
+    /// Gitignore-style glob of paths to scan even if they are ignored by default or by --exclude
+    #[arg(long = "include", value_name = "GLOB", global = true)]
+    pub include: Vec<String>,
+
+    /// Do not honor .gitignore, .ignore and .huntignore files while discovering source files
+    #[arg(long = "no-ignore-files", global = true)]
+    pub no_ignore_files: bool,
+
+    /// Additional source file extension to scan, optionally with a scanner: `vue`, `mdx=text`.
+    /// Scanners: text (whole file), script (JS/TS, skips comments), markup (HTML-like templates), mdx
+    #[arg(long = "ext", value_name = "EXT[=SCANNER]", global = true)]
+    pub extensions: Vec<String>,
+
+    /// How key usages are found: `regex` matches keys anywhere in a file, `ast` only collects
+    /// string arguments of translation call sites (t(), i18n.t(), <Trans i18nKey>, ...).
+    /// Defaults to `regex`, or `ast` with namespaces, which need it
+    #[arg(long = "extraction", value_name = "regex|ast", global = true)]
+    pub extraction: Option<String>,
+
+    /// Translation function recognized by `--extraction ast` (can specify multiple). Defaults to t, i18n.t, i18next.t and $t
+    #[arg(long = "function", value_name = "NAME", global = true)]
+    pub functions: Vec<String>,
+
+    /// Component and key attribute recognized by `--extraction ast`, e.g. `Trans:i18nKey` (can specify multiple)
+    #[arg(long = "component", value_name = "COMPONENT:ATTR", global = true)]
+    pub components: Vec<String>,
+
+    /// Treat each JSON file as an i18next namespace named after the file; keys are reported as `namespace:key`
+    #[arg(long = "namespaces", global = true, overrides_with = "no_namespaces")]
+    pub namespaces: bool,
+
+    /// Do not use namespaces, even if the config file turns them on
+    #[arg(
+        long = "no-namespaces",
+        global = true,
+        conflicts_with = "default_namespace"
+    )]
+    pub no_namespaces: bool,
+
+    /// Namespace used for keys without an explicit one (implies --namespaces). Defaults to `translation`
+    #[arg(long = "default-ns", value_name = "NAMESPACE", global = true)]
+    pub default_namespace: Option<String>,
+
+    /// Treat the translation path as a locales root (`{lng}/{ns}.json` or `{lng}.json`) and detect usage
+    /// against this locale. --clear then cleans every locale
+    #[arg(long = "source-locale", value_name = "LNG", global = true)]
+    pub source_locale: Option<String>,
+
+    /// How arrays in translation files map to keys: `elements` makes every element a key (`items[0]`)
+    /// that --clear removes on its own, `atomic` makes the whole array one key (`items`), as read with returnObjects
+    #[arg(long = "arrays", value_name = "elements|atomic", global = true)]
+    pub arrays: Option<String>,
+
+    /// i18next plural suffixes whose keys are used through their base key (`items_one` via `t('items', { count })`):
+    /// `v4` for CLDR categories (`_one`, `_other`, `_ordinal_few`), `v3` for `_plural` and `_0`, `_1`, ...
+    #[arg(long = "plurals", value_name = "v4|v3|none", global = true)]
+    pub plurals: Option<String>,
+
+    /// i18next context value whose `key_<context>` variants are used through `key` (can specify multiple);
+    /// `*` accepts any suffix
+    #[arg(long = "context", value_name = "VALUE", global = true)]
+    pub contexts: Vec<String>,
+
+    /// Key that is never reported or removed, because it is used outside the scanned code (can specify
+    /// multiple): an exact key, `prefix.*`, or a glob (`*` within a segment, `**` across segments).
+    /// Added to the config file's keep list
+    #[arg(long = "keep", value_name = "PATTERN", global = true)]
+    pub keep: Vec<String>,
+
+    /// Loaded config file (empty when none was found)
+    #[arg(skip)]
+    pub config: Config,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show every file, line and column where a translation key is used
+    Where {
+        /// Translation key. With namespaces, keys without `namespace:` are looked up in the default namespace
+        key: String,
+        /// Path to the translation file (JSON) or directory, when not given before `where` or in the
+        /// config file
+        translation_path: Option<String>,
+    },
+    /// Put keys removed by --clear back where they were, from the archive it wrote
+    Restore {
+        /// Archive file written by --clear
+        archive: String,
+        /// Only restore matching keys: exact keys or `prefix.*` (can specify multiple)
+        #[arg(long = "key", value_name = "PATTERN")]
+        keys: Vec<String>,
+        /// Recreate translation files that no longer exist
+        #[arg(long)]
+        create: bool,
+    },
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
-        Self::parse()
+        let mut cli = Self::parse();
+        // `hunt where <key> <path>` names the translations after the subcommand
+        if let Some(Command::Where {
+            translation_path: Some(path),
+            ..
+        }) = &cli.command
+        {
+            cli.translation_path = Some(path.clone());
+        }
+        cli
+    }
+
+    /// Print the help and exit when hunt is run without arguments and no config file names the
+    /// translations
+    pub fn exit_with_help_if_empty(&self) {
+        let no_arguments = std::env::args_os().len() <= 1;
+        if no_arguments && self.translation_path.is_none() && self.command.is_none() {
+            let _ = Self::command().print_help();
+            std::process::exit(2);
+        }
+    }
+
+    /// Fill in everything not given on the command line from the config file
+    pub fn apply_config(&mut self, config: Config) {
+        if self.translation_path.is_none() {
+            self.translation_path = config
+                .translations
+                .as_deref()
+                .map(|p| config.resolve_path(p));
+        }
+
+        if self.source_dirs.iter().all(|dir| dir.is_empty()) {
+            self.source_dirs = config
+                .dirs
+                .iter()
+                .map(|dir| config.resolve_path(dir))
+                .collect();
+        }
+
+        if self.extraction.is_none() {
+            self.extraction = config.extraction.clone();
+        }
+        if self.functions.is_empty() {
+            self.functions = config.functions.clone();
+        }
+        if self.components.is_empty() {
+            self.components = config.components.clone();
+        }
+
+        if self.default_namespace.is_none() {
+            self.default_namespace = config.default_namespace.clone();
+        }
+
+        if self.source_locale.is_none() {
+            self.source_locale = config.source_locale.clone();
+        }
+        if self.arrays.is_none() {
+            self.arrays = config.arrays.clone();
+        }
+        if self.plurals.is_none() {
+            self.plurals = config.plurals.clone();
+        }
+        if self.contexts.is_empty() {
+            self.contexts = config.contexts.clone();
+        }
+        if self.baseline.is_none() {
+            self.baseline = config.baseline.as_deref().map(|p| config.resolve_path(p));
+        }
+        if self.archive_dir.is_none() {
+            self.archive_dir = config
+                .archive_dir
+                .as_deref()
+                .map(|p| config.resolve_path(p));
+        }
+
+        if self.format.is_none() {
+            self.format = config.output.format.clone();
+        }
+
+        // Command-line extensions come last so they override the config's scanner choice
+        self.extensions = config
+            .extensions
+            .iter()
+            .cloned()
+            .chain(self.extensions.drain(..))
+            .collect();
+        self.keep = config
+            .keep
+            .iter()
+            .cloned()
+            .chain(self.keep.drain(..))
+            .collect();
+        self.config = config;
+    }
+
+    /// Ignore rule sets, command line first so it takes precedence over the config file
+    pub fn ignore_rules(&self) -> Vec<IgnoreRules<'_>> {
+        vec![
+            IgnoreRules {
+                root: Path::new("."),
+                exclude: &self.exclude,
+                include: &self.include,
+            },
+            IgnoreRules {
+                root: &self.config.base_dir,
+                exclude: &self.config.exclude,
+                include: &self.config.include,
+            },
+        ]
+    }
+
+    /// Translation path from the command line or config file
+    pub fn translation_path(&self) -> Result<&str, Box<dyn std::error::Error>> {
+        self.translation_path.as_deref().ok_or_else(|| {
+            "No translation path given (pass it as an argument or set `translations` in hunt.toml)".into()
+        })
+    }
+
+    /// Options for searching source files
+    pub fn search_options(&self) -> Result<SearchOptions, Box<dyn std::error::Error>> {
+        Ok(SearchOptions {
+            scanners: crate::scanner::build_scanner_map(&self.extensions)?,
+            extraction: self.extraction()?,
+            call_sites: CallSites::new(&self.functions, &self.components)?,
+            default_namespace: self.namespaced().then(|| {
+                self.default_namespace
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_NAMESPACE.to_string())
+            }),
+            collect_locations: self.with_locations
+                || matches!(self.command, Some(Command::Where { .. })),
+            keep: KeepList::new(&self.keep)?,
+            variants: Variants::new(
+                PluralFormat::from_name(self.plurals.as_deref().unwrap_or("v4"))?,
+                &self.contexts,
+            ),
+        })
+    }
+
+    /// How key usages are found. Namespaces are resolved per call site, so they need (and default
+    /// to) the AST extraction
+    pub fn extraction(&self) -> Result<Extraction, Box<dyn std::error::Error>> {
+        let default = if self.namespaced() { "ast" } else { "regex" };
+        let extraction = Extraction::from_name(self.extraction.as_deref().unwrap_or(default))?;
+        if self.namespaced() && extraction == Extraction::Regex {
+            return Err(
+                "Namespaces need --extraction ast, which resolves the namespace of every call site"
+                    .into(),
+            );
+        }
+        Ok(extraction)
+    }
+
+    /// Report format from the command line or config file
+    pub fn format(&self) -> Result<Format, Box<dyn std::error::Error>> {
+        Format::from_name(self.format.as_deref().unwrap_or("human"))
+    }
+
+    /// How arrays in translation files map to keys
+    pub fn array_mode(&self) -> Result<ArrayMode, Box<dyn std::error::Error>> {
+        ArrayMode::from_name(self.arrays.as_deref().unwrap_or("elements"))
+    }
+
+    /// Whether translation keys are qualified with their file's namespace
+    pub fn namespaced(&self) -> bool {
+        flag(self.namespaces, self.no_namespaces).unwrap_or_else(|| {
+            self.config.namespaces.unwrap_or(false) || self.default_namespace.is_some()
+        })
+    }
+
+    /// Whether statistics are shown
+    pub fn show_stats(&self) -> bool {
+        flag(self.show_stats, self.no_stats)
+            .or(self.config.output.stats)
+            .unwrap_or(false)
+    }
+
+    /// Whether the list of unused keys is shown
+    pub fn show_keys(&self) -> bool {
+        flag(self.show_keys, self.no_keys)
+            .or(self.config.output.keys)
+            .unwrap_or(false)
+    }
+
+    /// Whether .gitignore, .ignore and .huntignore files are honored during discovery
+    pub fn respect_ignore_files(&self) -> bool {
+        !self.no_ignore_files && self.config.ignore_files.unwrap_or(true)
+    }
+
+    /// How --clear rewrites translation files
+    pub fn cleanup_options(&self) -> CleanupOptions {
+        CleanupOptions {
+            namespaced: self.namespaced(),
+            prune_empty: !self.keep_empty && self.config.prune_empty.unwrap_or(true),
+        }
+    }
+
+    /// Directory to archive keys removed by --clear in, None when archiving is off
+    pub fn archive_dir(&self) -> Option<&str> {
+        if self.no_archive || !self.config.archive.unwrap_or(true) {
+            return None;
+        }
+        Some(
+            self.archive_dir
+                .as_deref()
+                .unwrap_or(crate::archive::DEFAULT_ARCHIVE_DIR),
+        )
+    }
+
+    /// Translation file or directory that usage is detected against
+    pub fn source_translation_path(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let translation_path = self.translation_path()?;
+        let Some(locale) = &self.source_locale else {
+            return Ok(translation_path.to_string());
+        };
+
+        let path = crate::translation::locale_path(translation_path, locale)?;
+        if self.namespaced() && path.is_file() {
+            return Err(format!(
+                "Namespaces need one directory per locale ({}/{}/<namespace>.json)",
+                translation_path, locale
+            )
+            .into());
+        }
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    /// Translation files or directories rewritten by --clear: every locale when a source locale is set
+    pub fn clear_targets(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let translation_path = self.translation_path()?;
+        if self.source_locale.is_none() {
+            return Ok(vec![translation_path.to_string()]);
+        }
+
+        Ok(crate::translation::find_locales(translation_path)?
+            .into_iter()
+            .map(|(_, path)| path.to_string_lossy().to_string())
+            .collect())
     }
 
     pub fn validate_source_dirs(&self) -> Vec<String> {
@@ -52,3 +475,41 @@ impl Cli {
         }
     }
 }
+
+/// Value of an on/off flag pair, None when neither was given on the command line
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_flags_override_config() {
+        let config = || -> Config {
+            toml::from_str("namespaces = true\n\n[output]\nstats = true\nkeys = true\n").unwrap()
+        };
+
+        let mut cli = Cli::parse_from(["hunt", "locales/en"]);
+        cli.apply_config(config());
+        assert!(cli.namespaced() && cli.show_stats() && cli.show_keys());
+
+        let mut cli = Cli::parse_from([
+            "hunt",
+            "locales/en",
+            "--no-namespaces",
+            "--no-stats",
+            "--no-keys",
+        ]);
+        cli.apply_config(config());
+        assert!(!cli.namespaced() && !cli.show_stats() && !cli.show_keys());
+
+        let cli = Cli::parse_from(["hunt", "locales/en", "--stats", "--no-stats"]);
+        assert!(!cli.show_stats());
+    }
+}
@@ -0,0 +1,280 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config file names, in order of preference, looked up in every directory from cwd to root
+const CONFIG_FILE_NAMES: &[&str] = &["hunt.toml", ".huntrc"];
+
+/// Project configuration read from `hunt.toml` or `.huntrc`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Path to the translation file or directory
+    pub translations: Option<String>,
+    /// Source directories to search
+    pub dirs: Vec<String>,
+    /// Additional source file extensions to scan, as `ext` or `ext=scanner`
+    pub extensions: Vec<String>,
+    /// Extra ignore patterns
+    pub exclude: Vec<String>,
+    /// Patterns that are scanned even when they match an ignore pattern
+    pub include: Vec<String>,
+    /// Honor .gitignore, .ignore and .huntignore files (defaults to true)
+    pub ignore_files: Option<bool>,
+    /// How key usages are found: "regex" (default) or "ast"
+    pub extraction: Option<String>,
+    /// Translation functions recognized by AST extraction
+    pub functions: Vec<String>,
+    /// `Component:attribute` pairs recognized by AST extraction
+    pub components: Vec<String>,
+    /// Treat each JSON file as a namespace named after the file
+    pub namespaces: Option<bool>,
+    /// Namespace for keys without an explicit one (defaults to "translation")
+    pub default_namespace: Option<String>,
+    /// Locale used for usage detection when `translations` is a locales root
+    pub source_locale: Option<String>,
+    /// Keys that are never reported or removed (exact keys, `prefix.*` or globs)
+    pub keep: Vec<String>,
+    /// Baseline file of accepted unused keys
+    pub baseline: Option<String>,
+    /// Archive keys removed by --clear so `hunt restore` can put them back (defaults to true)
+    pub archive: Option<bool>,
+    /// Directory archives are written to (defaults to .hunt-archive)
+    pub archive_dir: Option<String>,
+    /// How arrays map to keys: "elements" (default) or "atomic"
+    pub arrays: Option<String>,
+    /// i18next plural suffixes: "v4" (default), "v3" or "none"
+    pub plurals: Option<String>,
+    /// i18next context values whose `key_<context>` variants are used through `key` ("*" for any)
+    pub contexts: Vec<String>,
+    /// Remove objects and arrays left empty by --clear (defaults to true)
+    pub prune_empty: Option<bool>,
+    /// Output options
+    pub output: OutputConfig,
+
+    /// Directory the config file was found in; relative paths are resolved against it
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+    /// Path of the config file, when one was loaded
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// `[output]` section of the config file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub stats: Option<bool>,
+    pub keys: Option<bool>,
+    /// Report format: "human" (default), "json" or "sarif"
+    pub format: Option<String>,
+}
+
+impl Config {
+    /// Resolve a path from the config file relative to the config file's directory
+    pub fn resolve_path(&self, path: &str) -> String {
+        let candidate = Path::new(path);
+        if candidate.is_absolute()
+            || self.base_dir.as_os_str().is_empty()
+            || self.base_dir == Path::new(".")
+        {
+            path.to_string()
+        } else {
+            self.base_dir.join(candidate).to_string_lossy().to_string()
+        }
+    }
+}
+
+/// Load the config from an explicit path, or discover it by walking up from the current directory
+pub fn load_config(
+    explicit_path: Option<&str>,
+) -> Result<Option<Config>, Box<dyn std::error::Error>> {
+    let config_path = match explicit_path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(format!("Config file does not exist: {}", path.display()).into());
+            }
+            path
+        }
+        None => match discover_config_file(&std::env::current_dir()?) {
+            Some(path) => path,
+            None => return Ok(None),
+        },
+    };
+
+    let mut config = parse_config_file(&config_path)?;
+    config.base_dir = config_base_dir(&config_path)?;
+    config.path = Some(config_path);
+    Ok(Some(config))
+}
+
+/// Walk up from `start` and return the first config file found
+fn discover_config_file(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        for name in CONFIG_FILE_NAMES {
+            let candidate = dir.join(name);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+/// Directory of the config file, as "." when it is the current directory
+fn config_base_dir(config_path: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let parent = match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => return Ok(PathBuf::from(".")),
+    };
+
+    let cwd = std::env::current_dir()?;
+    if parent.canonicalize().ok() == cwd.canonicalize().ok() {
+        Ok(PathBuf::from("."))
+    } else {
+        Ok(parent)
+    }
+}
+
+/// Parse a config file (TOML, or JSON for a `.huntrc` starting with `{`)
+fn parse_config_file(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    parse_config(&content)
+        .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into())
+}
+
+fn parse_config(content: &str) -> Result<Config, Box<dyn std::error::Error>> {
+    if content.trim_start().starts_with('{') {
+        Ok(serde_json::from_str(content)?)
+    } else {
+        Ok(toml::from_str(content)?)
+    }
+}
+
+/// Append patterns to the `keep` list of a config file, creating `hunt.toml` in the current
+/// directory when there is none. TOML files keep their formatting and comments; nothing is
+/// written with `dry_run`. Returns the path and how many patterns were new
+pub fn add_keep_patterns(
+    config_path: Option<&Path>,
+    patterns: &[String],
+    dry_run: bool,
+) -> Result<(PathBuf, usize), Box<dyn std::error::Error>> {
+    let path = config_path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAMES[0]));
+    let content = if path.is_file() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    let (updated, added) = add_keep_patterns_to(&content, patterns)
+        .map_err(|e| format!("Failed to update keep list in {}: {}", path.display(), e))?;
+    if added > 0 && !dry_run {
+        fs::write(&path, updated)?;
+    }
+    Ok((path, added))
+}
+
+fn add_keep_patterns_to(
+    content: &str,
+    patterns: &[String],
+) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let mut added = 0;
+
+    if content.trim_start().starts_with('{') {
+        let mut config: serde_json::Value = serde_json::from_str(content)?;
+        let keep = config
+            .as_object_mut()
+            .ok_or("config is not a JSON object")?
+            .entry("keep")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+            .as_array_mut()
+            .ok_or("`keep` is not an array")?;
+        for pattern in patterns {
+            if !keep.iter().any(|entry| entry.as_str() == Some(pattern)) {
+                keep.push(pattern.clone().into());
+                added += 1;
+            }
+        }
+        return Ok((
+            format!("{}\n", serde_json::to_string_pretty(&config)?),
+            added,
+        ));
+    }
+
+    let mut document: toml_edit::DocumentMut = content.parse()?;
+    let keep = document
+        .entry("keep")
+        .or_insert_with(|| toml_edit::value(toml_edit::Array::new()))
+        .as_array_mut()
+        .ok_or("`keep` is not an array")?;
+    for pattern in patterns {
+        if !keep.iter().any(|entry| entry.as_str() == Some(pattern)) {
+            keep.push(pattern.as_str());
+            added += 1;
+        }
+    }
+    Ok((document.to_string(), added))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_config() {
+        let config = parse_config(
+            r#"
+translations = "public/locales/en-US"
+dirs = ["src", "lib"]
+keep = ["errors.*"]
+
+[output]
+stats = true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.translations.as_deref(), Some("public/locales/en-US"));
+        assert_eq!(config.dirs, vec!["src", "lib"]);
+        assert_eq!(config.output.stats, Some(true));
+        assert_eq!(config.output.keys, None);
+    }
+
+    #[test]
+    fn test_parse_json_config() {
+        let config =
+            parse_config(r#"{ "translations": "locales", "exclude": ["legacy"] }"#).unwrap();
+
+        assert_eq!(config.translations.as_deref(), Some("locales"));
+        assert_eq!(config.exclude, vec!["legacy"]);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        assert!(parse_config("translation = \"typo\"").is_err());
+    }
+
+    #[test]
+    fn test_add_keep_patterns() {
+        let toml = "# project config\ntranslations = \"locales\"\nkeep = [\"errors.*\"]\n\n[output]\nkeys = true\n";
+        let patterns = vec!["errors.*".to_string(), "legacy.*".to_string()];
+        let (updated, added) = add_keep_patterns_to(toml, &patterns).unwrap();
+
+        assert_eq!(added, 1);
+        assert!(updated.starts_with("# project config\n"));
+        assert_eq!(
+            parse_config(&updated).unwrap().keep,
+            vec!["errors.*", "legacy.*"]
+        );
+
+        let (updated, added) = add_keep_patterns_to("", &patterns).unwrap();
+        assert_eq!(added, 2);
+        assert_eq!(parse_config(&updated).unwrap().keep.len(), 2);
+
+        let (updated, _) = add_keep_patterns_to("{ \"dirs\": [\"src\"] }", &patterns).unwrap();
+        assert_eq!(parse_config(&updated).unwrap().keep.len(), 2);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::translation::FileChange;
+use similar::TextDiff;
+use std::path::{Component, Path};
+
+/// Lines of context around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Unified diff of a planned change, with `a/` and `b/` headers so the patch applies with `git apply`
+pub fn unified_diff(change: &FileChange) -> String {
+    let path = patch_path(&change.path);
+    TextDiff::from_lines(&change.original, &change.updated)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(&format!("a/{}", path), &format!("b/{}", path))
+        .to_string()
+}
+
+/// All planned changes as a single patch
+pub fn patch(changes: &[FileChange]) -> String {
+    changes.iter().map(unified_diff).collect()
+}
+
+/// Path as written in patch headers: relative to the current directory when possible, with forward slashes
+fn patch_path(path: &Path) -> String {
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path);
+
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_unified_diff() {
+        let change = FileChange {
+            path: PathBuf::from("./locales/en.json"),
+            original: "{\n  \"a\": \"A\",\n  \"b\": \"B\"\n}\n".to_string(),
+            updated: "{\n  \"a\": \"A\"\n}\n".to_string(),
+            removed: Vec::new(),
+            kept: Vec::new(),
+        };
+        let diff = unified_diff(&change);
+
+        assert!(diff.starts_with("--- a/locales/en.json\n+++ b/locales/en.json\n"));
+        assert!(diff.contains("-  \"a\": \"A\",\n"));
+        assert!(diff.contains("-  \"b\": \"B\"\n"));
+        assert!(diff.contains("+  \"a\": \"A\"\n"));
+    }
+}
@@ -0,0 +1,1482 @@
+//! Language-aware key extraction for JavaScript/TypeScript (with JSX) and HTML-like templates.
+//!
+//! Instead of matching keys anywhere in a file, source code is tokenized (skipping comments,
+//! understanding strings, template literals, regex literals and JSX) and only string arguments
+//! of configured translation call sites are collected, e.g. `t('key')`, `i18n.t("key")`,
+//! `<Trans i18nKey="key">` or `<FormattedMessage id="key" />`.
+
+use crate::template::{KeyTemplate, TemplatePart};
+
+/// Translation functions recognized by default
+const DEFAULT_FUNCTIONS: &[&str] = &["t", "i18n.t", "i18next.t", "$t"];
+
+/// Components and the attribute holding the key, recognized by default
+const DEFAULT_COMPONENTS: &[&str] = &["Trans:i18nKey", "FormattedMessage:id"];
+
+/// Keywords after which a `/` starts a regex literal and a `<` starts a JSX element
+const EXPRESSION_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "case",
+    "do",
+    "else",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "instanceof",
+    "yield",
+    "await",
+    "default",
+];
+
+/// Elements whose body is not markup in HTML-like templates
+const RAW_TEXT_ELEMENTS: &[&str] = &["style", "textarea", "pre"];
+
+/// How key usages are found in source files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// Match every key as a whole word anywhere in a file
+    Regex,
+    /// Parse source files and collect only arguments of translation call sites
+    Ast,
+}
+
+impl Extraction {
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "regex" => Ok(Extraction::Regex),
+            "ast" => Ok(Extraction::Ast),
+            _ => Err(format!("Unknown extraction '{}' (expected regex or ast)", name).into()),
+        }
+    }
+}
+
+/// Translation call sites: function names and `Component:attribute` pairs
+#[derive(Debug, Clone)]
+pub struct CallSites {
+    functions: Vec<String>,
+    components: Vec<(String, String)>,
+}
+
+impl CallSites {
+    /// Build call sites, falling back to the defaults for any empty list
+    pub fn new(
+        functions: &[String],
+        components: &[String],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let functions = if functions.is_empty() {
+            DEFAULT_FUNCTIONS.iter().map(|f| f.to_string()).collect()
+        } else {
+            functions.to_vec()
+        };
+
+        let component_specs: Vec<String> = if components.is_empty() {
+            DEFAULT_COMPONENTS.iter().map(|c| c.to_string()).collect()
+        } else {
+            components.to_vec()
+        };
+
+        let mut parsed = Vec::new();
+        for spec in component_specs {
+            match spec.split_once(':') {
+                Some((component, attribute)) if !component.is_empty() && !attribute.is_empty() => {
+                    parsed.push((component.to_string(), attribute.to_string()));
+                }
+                _ => {
+                    return Err(format!(
+                        "Invalid component '{}' (expected Component:attribute, e.g. Trans:i18nKey)",
+                        spec
+                    )
+                    .into())
+                }
+            }
+        }
+
+        Ok(CallSites {
+            functions,
+            components: parsed,
+        })
+    }
+
+    fn is_function(&self, callee: &str) -> bool {
+        let callee = callee.strip_prefix("this.").unwrap_or(callee);
+        self.functions.iter().any(|f| f == callee)
+    }
+
+    fn key_attribute(&self, component: &str) -> Option<&str> {
+        self.components
+            .iter()
+            .find(|(name, _)| name == component)
+            .map(|(_, attribute)| attribute.as_str())
+    }
+}
+
+/// A key reference found at a call site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyUsage {
+    /// A fully literal key: `t('status.open')`
+    Literal(String),
+    /// A key built at runtime: `` t(`errors.${code}.title`) ``, `t('status.' + code)`
+    Dynamic(KeyTemplate),
+}
+
+/// A key reference with its position (1-based line and column)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedUsage {
+    pub key: KeyUsage,
+    /// Namespace bound at the call site (`ns` option or `useTranslation('ns')`), if any.
+    /// A `ns:` prefix inside the key itself takes precedence
+    pub namespace: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Source language of a file, which decides how it is tokenized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// JavaScript or TypeScript that may contain JSX
+    Jsx,
+    /// TypeScript without JSX (`<Type>value` casts are not elements)
+    TypeScript,
+    /// HTML-like template with embedded `<script>` blocks and `{ }` expressions
+    Markup,
+    /// MDX: markdown with JSX elements, `{ }` expressions and `import`/`export` blocks
+    Mdx,
+}
+
+impl SourceKind {
+    pub fn for_path(path: &std::path::Path, markup: bool) -> Self {
+        if markup {
+            return SourceKind::Markup;
+        }
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ts") | Some("mts") | Some("cts") => SourceKind::TypeScript,
+            _ => SourceKind::Jsx,
+        }
+    }
+}
+
+/// Extract all key usages at configured call sites from a source file
+pub fn extract_usages(
+    source: &str,
+    kind: SourceKind,
+    call_sites: &CallSites,
+) -> Vec<ExtractedUsage> {
+    let tokens = tokenize(source, kind);
+    let lines = LineIndex::new(source);
+    let mut usages = Vec::new();
+    // Namespace bound by useTranslation()/withTranslation()/getFixedT(), per enclosing brace
+    // block: a binding holds until the block it was made in closes
+    let mut scopes: Vec<Option<String>> = vec![None];
+
+    for i in 0..tokens.len() {
+        let bound_namespace = scopes.last().cloned().flatten();
+        match &tokens[i].kind {
+            TokenKind::Punct('{') => scopes.push(bound_namespace),
+            TokenKind::Punct('}') if scopes.len() > 1 => {
+                scopes.pop();
+            }
+            TokenKind::Ident(_) if !is_member_continuation(&tokens, i) => {
+                let Some((callee, open_paren)) = callee_at(&tokens, i) else {
+                    continue;
+                };
+                let args = call_arguments(&tokens, open_paren);
+
+                if call_sites.is_function(&callee) {
+                    let namespace = option_value(&tokens, &args, "ns")
+                        .and_then(|token| first_string(&tokens, token))
+                        .or(bound_namespace);
+                    if let Some(&first) = args.first() {
+                        collect_argument(&tokens, first, namespace, &lines, &mut usages);
+                    }
+                } else if let Some(position) = namespace_argument(&callee) {
+                    // `useTranslation()` without a namespace binds the default one
+                    if let Some(scope) = scopes.last_mut() {
+                        *scope = args
+                            .get(position)
+                            .and_then(|&arg| first_string(&tokens, arg));
+                    }
+                }
+            }
+            TokenKind::Punct('<') => {
+                if let Some(TokenKind::Ident(component)) = tokens.get(i + 1).map(|t| &t.kind) {
+                    if let Some(attribute) = call_sites.key_attribute(component) {
+                        let attributes = tag_attributes(&tokens, i + 2);
+                        let namespace = attributes
+                            .iter()
+                            .find(|(name, _)| name == "ns")
+                            .and_then(|(_, value)| first_string(&tokens, *value))
+                            .or(bound_namespace);
+                        if let Some((_, value)) =
+                            attributes.iter().find(|(name, _)| name == attribute)
+                        {
+                            push_key(&tokens[*value], namespace, &lines, &mut usages);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    usages
+}
+
+/// Functions binding a namespace for subsequent `t()` calls, and which argument holds it
+fn namespace_argument(callee: &str) -> Option<usize> {
+    let name = callee.rsplit('.').next().unwrap_or(callee);
+    match name {
+        "useTranslation" | "withTranslation" | "translate" => Some(0),
+        "getFixedT" => Some(1),
+        _ => None,
+    }
+}
+
+/// Whether the identifier at `i` is preceded by `.` (part of a longer member chain)
+fn is_member_continuation(tokens: &[Token], i: usize) -> bool {
+    i > 0 && tokens[i - 1].kind == TokenKind::Punct('.')
+}
+
+/// Read a dotted callee (`i18n.t`) starting at `i`; returns it with the index of the `(` token
+fn callee_at(tokens: &[Token], i: usize) -> Option<(String, usize)> {
+    let mut callee = match &tokens[i].kind {
+        TokenKind::Ident(name) => name.clone(),
+        _ => return None,
+    };
+    let mut j = i + 1;
+
+    loop {
+        match tokens.get(j).map(|t| &t.kind) {
+            Some(TokenKind::Punct('.')) => match tokens.get(j + 1).map(|t| &t.kind) {
+                Some(TokenKind::Ident(name)) => {
+                    callee.push('.');
+                    callee.push_str(name);
+                    j += 2;
+                }
+                _ => return None,
+            },
+            Some(TokenKind::Punct('(')) => return Some((callee, j)),
+            _ => return None,
+        }
+    }
+}
+
+/// Indices of the first token of each argument of the call whose `(` is at `open_paren`
+fn call_arguments(tokens: &[Token], open_paren: usize) -> Vec<usize> {
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut expect_argument = true;
+
+    for (j, token) in tokens.iter().enumerate().skip(open_paren + 1) {
+        match token.kind {
+            TokenKind::Punct('(') | TokenKind::Punct('[') | TokenKind::Punct('{') => {
+                if depth == 0 && expect_argument {
+                    args.push(j);
+                    expect_argument = false;
+                }
+                depth += 1;
+            }
+            TokenKind::Punct(')') | TokenKind::Punct(']') | TokenKind::Punct('}') => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            TokenKind::Punct(',') if depth == 0 => expect_argument = true,
+            _ => {
+                if depth == 0 && expect_argument {
+                    args.push(j);
+                    expect_argument = false;
+                }
+            }
+        }
+    }
+
+    args
+}
+
+/// Value token of `name` in an options object passed as any argument after the first,
+/// e.g. `ns` in `t('key', { ns: 'billing' })`. Shorthand properties yield the name token
+fn option_value(tokens: &[Token], args: &[usize], name: &str) -> Option<usize> {
+    args.iter()
+        .skip(1)
+        .filter(|&&arg| tokens[arg].kind == TokenKind::Punct('{'))
+        .find_map(|&arg| {
+            object_properties(tokens, arg)
+                .into_iter()
+                .find(|(key, _)| key == name)
+        })
+        .map(|(_, value)| value)
+}
+
+/// Top-level properties of the object literal whose `{` is at `open_brace`, with value token indices
+fn object_properties(tokens: &[Token], open_brace: usize) -> Vec<(String, usize)> {
+    let mut properties = Vec::new();
+    let mut depth = 0usize;
+    let mut expect_key = true;
+    let mut j = open_brace + 1;
+
+    while let Some(token) = tokens.get(j) {
+        match &token.kind {
+            TokenKind::Punct('(') | TokenKind::Punct('[') | TokenKind::Punct('{') => depth += 1,
+            TokenKind::Punct(')') | TokenKind::Punct(']') | TokenKind::Punct('}') => {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            }
+            TokenKind::Punct(',') if depth == 0 => expect_key = true,
+            TokenKind::Ident(key) | TokenKind::Str(key) if depth == 0 && expect_key => {
+                expect_key = false;
+                if tokens.get(j + 1).map(|t| &t.kind) == Some(&TokenKind::Punct(':')) {
+                    properties.push((key.clone(), j + 2));
+                    j += 1;
+                } else {
+                    properties.push((key.clone(), j));
+                }
+            }
+            _ => {}
+        }
+        j += 1;
+    }
+
+    properties
+}
+
+/// Attributes of the opening tag starting after the component name, with value token indices.
+/// For `attr={value}` the index points inside the braces
+fn tag_attributes(tokens: &[Token], mut i: usize) -> Vec<(String, usize)> {
+    let mut attributes = Vec::new();
+    let mut depth = 0usize;
+
+    while let Some(token) = tokens.get(i) {
+        match &token.kind {
+            TokenKind::Punct('{') => depth += 1,
+            TokenKind::Punct('}') => depth = depth.saturating_sub(1),
+            TokenKind::Punct('>') if depth == 0 => break,
+            TokenKind::Ident(name)
+                if depth == 0
+                    && tokens.get(i + 1).map(|t| &t.kind) == Some(&TokenKind::Punct('=')) =>
+            {
+                let value = match tokens.get(i + 2).map(|t| &t.kind) {
+                    Some(TokenKind::Punct('{')) => i + 3,
+                    _ => i + 2,
+                };
+                if value < tokens.len() {
+                    attributes.push((name.clone(), value));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    attributes
+}
+
+/// The string at `i`, or the first string of an array literal starting at `i`
+fn first_string(tokens: &[Token], i: usize) -> Option<String> {
+    match tokens.get(i).map(|t| &t.kind) {
+        Some(TokenKind::Str(value)) => Some(value.clone()),
+        Some(TokenKind::Punct('[')) => match tokens.get(i + 1).map(|t| &t.kind) {
+            Some(TokenKind::Str(value)) => Some(value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Collect the key(s) from a call's first argument: a string, template, concatenation,
+/// array of fallbacks, or array joined into a key (`['menu', id].join('.')`)
+fn collect_argument(
+    tokens: &[Token],
+    i: usize,
+    namespace: Option<String>,
+    lines: &LineIndex<'_>,
+    usages: &mut Vec<ExtractedUsage>,
+) {
+    match tokens.get(i).map(|t| &t.kind) {
+        Some(TokenKind::Punct('[')) => {
+            if let Some(parts) = joined_array(tokens, i) {
+                push_parts(&parts, tokens[i].start, namespace, lines, usages);
+                return;
+            }
+            let mut j = i + 1;
+            while let Some(token) = tokens.get(j) {
+                match &token.kind {
+                    TokenKind::Punct(']') => break,
+                    TokenKind::Punct(',') => {}
+                    TokenKind::Str(_) | TokenKind::Template(_) => {
+                        push_key(token, namespace.clone(), lines, usages)
+                    }
+                    _ => break,
+                }
+                j += 1;
+            }
+        }
+        Some(_) => {
+            if let Some((parts, _)) = concatenation(tokens, i) {
+                push_parts(&parts, tokens[i].start, namespace, lines, usages);
+            }
+        }
+        None => {}
+    }
+}
+
+/// Parts of a string or template token
+fn token_parts(token: &Token) -> Option<Vec<TemplatePart>> {
+    match &token.kind {
+        TokenKind::Str(value) => Some(vec![TemplatePart::Literal(value.clone())]),
+        TokenKind::Template(template) => {
+            let mut parts = vec![TemplatePart::Literal(template.quasis[0].clone())];
+            for (expr, quasi) in template.exprs.iter().zip(&template.quasis[1..]) {
+                parts.push(TemplatePart::Value(expr.clone()));
+                parts.push(TemplatePart::Literal(quasi.clone()));
+            }
+            Some(parts)
+        }
+        _ => None,
+    }
+}
+
+/// Parts of a `+` concatenation of strings and expressions starting at `i` (a single operand
+/// counts too), with the index of the token after it. Expressions become values
+fn concatenation(tokens: &[Token], i: usize) -> Option<(Vec<TemplatePart>, usize)> {
+    let mut parts = Vec::new();
+    let mut j = i;
+
+    loop {
+        match token_parts(tokens.get(j)?) {
+            Some(operand) => {
+                parts.extend(operand);
+                j += 1;
+            }
+            None => {
+                let end = expression_end(tokens, j)?;
+                parts.push(TemplatePart::Value(expression_text(&tokens[j..end])));
+                j = end;
+            }
+        }
+        match tokens.get(j).map(|t| &t.kind) {
+            Some(TokenKind::Punct('+')) => j += 1,
+            _ => return Some((parts, j)),
+        }
+    }
+}
+
+/// Index after a simple expression at `i`: an identifier or number followed by member accesses,
+/// calls and indexing (`item.codes[0].toLowerCase()`), or a parenthesized expression
+fn expression_end(tokens: &[Token], i: usize) -> Option<usize> {
+    let mut j = match tokens.get(i).map(|t| &t.kind)? {
+        TokenKind::Ident(_) | TokenKind::Other => i + 1,
+        TokenKind::Punct('(') => closing_bracket(tokens, i)? + 1,
+        _ => return None,
+    };
+
+    loop {
+        let kind = |offset: usize| tokens.get(j + offset).map(|t| &t.kind);
+        j = match (kind(0), kind(1), kind(2)) {
+            (Some(TokenKind::Punct('.')), Some(TokenKind::Ident(_)), _) => j + 2,
+            (
+                Some(TokenKind::Punct('?')),
+                Some(TokenKind::Punct('.')),
+                Some(TokenKind::Ident(_)),
+            ) => j + 3,
+            (Some(TokenKind::Punct('(')), _, _) | (Some(TokenKind::Punct('[')), _, _) => {
+                closing_bracket(tokens, j)? + 1
+            }
+            _ => return Some(j),
+        };
+    }
+}
+
+/// Source of a simple expression rebuilt from its tokens (`item?.status`)
+fn expression_text(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match &token.kind {
+            TokenKind::Ident(name) => name.clone(),
+            TokenKind::Str(value) => format!("'{}'", value),
+            TokenKind::Punct(c) => c.to_string(),
+            TokenKind::Template(_) | TokenKind::Other => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Index of the bracket closing the one at `open`
+fn closing_bracket(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (j, token) in tokens.iter().enumerate().skip(open) {
+        match token.kind {
+            TokenKind::Punct('(') | TokenKind::Punct('[') | TokenKind::Punct('{') => depth += 1,
+            TokenKind::Punct(')') | TokenKind::Punct(']') | TokenKind::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(j);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parts of `[...].join('sep')` with the array at `open`: elements joined by the separator
+fn joined_array(tokens: &[Token], open: usize) -> Option<Vec<TemplatePart>> {
+    let close = closing_bracket(tokens, open)?;
+    let kinds: Vec<&TokenKind> = tokens
+        .get(close + 1..close + 6)?
+        .iter()
+        .map(|t| &t.kind)
+        .collect();
+    let separator = match kinds[..] {
+        [TokenKind::Punct('.'), TokenKind::Ident(join), TokenKind::Punct('('), TokenKind::Str(separator), TokenKind::Punct(')')]
+            if join == "join" =>
+        {
+            separator
+        }
+        _ => return None,
+    };
+
+    let mut parts = Vec::new();
+    let mut j = open + 1;
+    while j < close {
+        if j > open + 1 {
+            parts.push(TemplatePart::Literal(separator.clone()));
+        }
+        // Elements that are not simple concatenations are unknown values
+        let (element, end) = match concatenation(tokens, j) {
+            Some((element, end)) if end <= close => (element, end),
+            _ => (vec![TemplatePart::Value(String::new())], j),
+        };
+        parts.extend(element);
+
+        // Skip to the element after the next top-level comma
+        j = end;
+        while j < close && tokens[j].kind != TokenKind::Punct(',') {
+            j = match tokens[j].kind {
+                TokenKind::Punct('(') | TokenKind::Punct('[') | TokenKind::Punct('{') => {
+                    closing_bracket(tokens, j)? + 1
+                }
+                _ => j + 1,
+            };
+        }
+        j += 1;
+    }
+
+    Some(parts)
+}
+
+/// Record a string or template token as a key usage
+fn push_key(
+    token: &Token,
+    namespace: Option<String>,
+    lines: &LineIndex<'_>,
+    usages: &mut Vec<ExtractedUsage>,
+) {
+    if let Some(parts) = token_parts(token) {
+        push_parts(&parts, token.start, namespace, lines, usages);
+    }
+}
+
+/// Record a key from its parts: a literal when all parts are known, a template otherwise
+fn push_parts(
+    parts: &[TemplatePart],
+    start: usize,
+    namespace: Option<String>,
+    lines: &LineIndex<'_>,
+    usages: &mut Vec<ExtractedUsage>,
+) {
+    let dynamic = parts
+        .iter()
+        .any(|part| matches!(part, TemplatePart::Value(_)));
+    let key = if dynamic {
+        match KeyTemplate::from_parts(parts) {
+            Some(template) => KeyUsage::Dynamic(template),
+            None => return,
+        }
+    } else {
+        let key: String = parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => text.as_str(),
+                TemplatePart::Value(_) => "",
+            })
+            .collect();
+        if key.is_empty() {
+            return;
+        }
+        KeyUsage::Literal(key)
+    };
+
+    let (line, column) = lines.position(start);
+    usages.push(ExtractedUsage {
+        key,
+        namespace,
+        line,
+        column,
+    });
+}
+
+/// Maps byte offsets to 1-based line and column numbers (columns count characters)
+pub struct LineIndex<'a> {
+    source: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self
+            .source
+            .get(line_start..offset)
+            .map_or(offset - line_start, |text| text.chars().count());
+        (line, column + 1)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Template {
+    /// Literal parts; always one more than `exprs`
+    quasis: Vec<String>,
+    /// Source text of each `${...}` interpolation
+    exprs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Str(String),
+    Template(Template),
+    Punct(char),
+    /// Numbers and regex literals
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+}
+
+/// Tokenize a whole file
+fn tokenize(source: &str, kind: SourceKind) -> Vec<Token> {
+    lex(source, kind).tokens
+}
+
+/// Byte ranges of the comments in a file (`//`, `/* */` and, in templates, `<!-- -->`), sorted.
+/// Regex literals, strings and JSX text are told apart from comments the same way as when
+/// extracting keys
+pub fn comment_ranges(source: &str, kind: SourceKind) -> Vec<(usize, usize)> {
+    let mut comments = lex(source, kind).comments;
+    comments.sort();
+    comments
+}
+
+fn lex(source: &str, kind: SourceKind) -> Lexer<'_> {
+    let mut lexer = Lexer {
+        src: source,
+        bytes: source.as_bytes(),
+        pos: 0,
+        offset: 0,
+        jsx: kind != SourceKind::TypeScript,
+        tokens: Vec::new(),
+        comments: Vec::new(),
+    };
+
+    match kind {
+        SourceKind::Markup => lexer.lex_markup(),
+        SourceKind::Mdx => lexer.lex_mdx(),
+        _ => lexer.lex_script(false),
+    }
+
+    lexer
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    /// Added to token positions when lexing a snippet embedded in a larger file
+    offset: usize,
+    jsx: bool,
+    tokens: Vec<Token>,
+    /// Byte ranges of the skipped `//` and `/* */` comments
+    comments: Vec<(usize, usize)>,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self, ahead: usize) -> Option<u8> {
+        self.bytes.get(self.pos + ahead).copied()
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize) {
+        self.tokens.push(Token {
+            kind,
+            start: start + self.offset,
+        });
+    }
+
+    /// Lex JavaScript until the end of input, or until an unmatched `}` when `in_braces`
+    fn lex_script(&mut self, in_braces: bool) {
+        let mut depth = 0usize;
+
+        while let Some(c) = self.peek(0) {
+            match c {
+                b' ' | b'\t' | b'\r' | b'\n' => self.pos += 1,
+                b'/' if self.peek(1) == Some(b'/') => self.skip_line_comment(),
+                b'/' if self.peek(1) == Some(b'*') => self.skip_block_comment(),
+                b'\'' | b'"' => self.lex_string(c),
+                b'`' => self.lex_template(),
+                b'/' if self.regex_allowed() => self.lex_regex(),
+                b'<' if self.jsx && self.expression_expected() && self.starts_element() => {
+                    self.lex_jsx_element()
+                }
+                b'{' => {
+                    depth += 1;
+                    self.push(TokenKind::Punct('{'), self.pos);
+                    self.pos += 1;
+                }
+                b'}' => {
+                    if in_braces && depth == 0 {
+                        return;
+                    }
+                    depth = depth.saturating_sub(1);
+                    self.push(TokenKind::Punct('}'), self.pos);
+                    self.pos += 1;
+                }
+                b'0'..=b'9' => {
+                    let start = self.pos;
+                    while self
+                        .peek(0)
+                        .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'_')
+                    {
+                        self.pos += 1;
+                    }
+                    self.push(TokenKind::Other, start);
+                }
+                _ if is_ident_start(self.current_char()) => self.lex_ident(),
+                _ => {
+                    let ch = self.current_char();
+                    self.push(TokenKind::Punct(ch), self.pos);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+    }
+
+    fn current_char(&self) -> char {
+        self.src[self.pos..].chars().next().unwrap_or('\0')
+    }
+
+    fn skip_line_comment(&mut self) {
+        let start = self.pos;
+        self.pos = self.src[self.pos..]
+            .find('\n')
+            .map_or(self.bytes.len(), |p| self.pos + p);
+        self.comments
+            .push((start + self.offset, self.pos + self.offset));
+    }
+
+    fn skip_block_comment(&mut self) {
+        let start = self.pos;
+        self.pos = self.src[self.pos + 2..]
+            .find("*/")
+            .map_or(self.bytes.len(), |p| self.pos + 2 + p + 2);
+        self.comments
+            .push((start + self.offset, self.pos + self.offset));
+    }
+
+    fn lex_ident(&mut self) {
+        let start = self.pos;
+        while self.pos < self.bytes.len() && is_ident_continue(self.current_char()) {
+            self.pos += self.current_char().len_utf8();
+        }
+        self.push(
+            TokenKind::Ident(self.src[start..self.pos].to_string()),
+            start,
+        );
+    }
+
+    /// Lex a quoted string. Unterminated strings (e.g. an apostrophe in text) become punctuation
+    fn lex_string(&mut self, quote: u8) {
+        let start = self.pos;
+        let mut value = String::new();
+        let mut i = self.pos + 1;
+
+        while i < self.bytes.len() {
+            let c = self.bytes[i];
+            if c == quote {
+                self.pos = i + 1;
+                self.push(TokenKind::Str(value), start);
+                return;
+            }
+            if c == b'\n' {
+                break;
+            }
+            if c == b'\\' {
+                i = read_escape(self.src, i, &mut value);
+                continue;
+            }
+            let ch = self.src[i..].chars().next().unwrap_or('\0');
+            value.push(ch);
+            i += ch.len_utf8();
+        }
+
+        self.push(TokenKind::Punct(quote as char), start);
+        self.pos = start + 1;
+    }
+
+    fn lex_template(&mut self) {
+        let start = self.pos;
+        let mut quasis = Vec::new();
+        let mut exprs = Vec::new();
+        let mut current = String::new();
+        self.pos += 1;
+
+        while self.pos < self.bytes.len() {
+            match self.bytes[self.pos] {
+                b'`' => {
+                    self.pos += 1;
+                    break;
+                }
+                b'\\' => self.pos = read_escape(self.src, self.pos, &mut current),
+                b'$' if self.peek(1) == Some(b'{') => {
+                    quasis.push(std::mem::take(&mut current));
+                    let expr_start = self.pos + 2;
+                    self.pos = skip_balanced_braces(self.src, expr_start);
+                    let expr_end = self.pos.min(self.bytes.len());
+                    exprs.push(self.src[expr_start..expr_end].trim().to_string());
+                    self.pos = (self.pos + 1).min(self.bytes.len());
+                }
+                _ => {
+                    let ch = self.current_char();
+                    current.push(ch);
+                    self.pos += ch.len_utf8();
+                }
+            }
+        }
+
+        quasis.push(current);
+        self.push(TokenKind::Template(Template { quasis, exprs }), start);
+    }
+
+    /// Lex a regex literal; if it does not close on the same line, treat `/` as division
+    fn lex_regex(&mut self) {
+        let start = self.pos;
+        let mut i = self.pos + 1;
+        let mut in_class = false;
+
+        while i < self.bytes.len() {
+            match self.bytes[i] {
+                b'\\' => i += 1,
+                b'[' => in_class = true,
+                b']' => in_class = false,
+                b'/' if !in_class => {
+                    i += 1;
+                    while i < self.bytes.len() && self.bytes[i].is_ascii_alphabetic() {
+                        i += 1;
+                    }
+                    self.pos = i;
+                    self.push(TokenKind::Other, start);
+                    return;
+                }
+                b'\n' => break,
+                _ => {}
+            }
+            i += 1;
+        }
+
+        self.push(TokenKind::Punct('/'), start);
+        self.pos = start + 1;
+    }
+
+    /// Whether the previous token leaves us at the start of an expression
+    fn expression_expected(&self) -> bool {
+        match self.tokens.last().map(|t| &t.kind) {
+            None => true,
+            Some(TokenKind::Punct(c)) => !matches!(c, ')' | ']' | '}'),
+            Some(TokenKind::Ident(word)) => EXPRESSION_KEYWORDS.contains(&word.as_str()),
+            Some(_) => false,
+        }
+    }
+
+    fn regex_allowed(&self) -> bool {
+        // `</` closes a JSX element; it is never a regex
+        if self.pos > 0 && self.bytes[self.pos - 1] == b'<' {
+            return false;
+        }
+        self.expression_expected()
+    }
+
+    fn starts_element(&self) -> bool {
+        match self.peek(1) {
+            Some(b'>') => true,
+            Some(_) => is_ident_start(self.src[self.pos + 1..].chars().next().unwrap_or('\0')),
+            None => false,
+        }
+    }
+
+    /// Lex a JSX element (or fragment) including its children
+    fn lex_jsx_element(&mut self) {
+        let self_closing = self.lex_tag(false);
+        if !self_closing {
+            self.lex_children(false);
+        }
+    }
+
+    /// Lex an opening tag starting at `<`. Returns true for self-closing tags (`/>`)
+    fn lex_tag(&mut self, markup: bool) -> bool {
+        self.push(TokenKind::Punct('<'), self.pos);
+        self.pos += 1;
+
+        // Tag name, allowing `Foo.Bar`, `foo-bar` and `foo:bar`
+        let name_start = self.pos;
+        while self.pos < self.bytes.len() {
+            let ch = self.current_char();
+            if is_ident_continue(ch) || ch == '.' || ch == '-' || ch == ':' {
+                self.pos += ch.len_utf8();
+            } else {
+                break;
+            }
+        }
+        if self.pos > name_start {
+            let name = self.src[name_start..self.pos].to_string();
+            self.push(TokenKind::Ident(name), name_start);
+        }
+
+        while let Some(c) = self.peek(0) {
+            match c {
+                b'/' if self.peek(1) == Some(b'>') => {
+                    self.push(TokenKind::Punct('/'), self.pos);
+                    self.push(TokenKind::Punct('>'), self.pos + 1);
+                    self.pos += 2;
+                    return true;
+                }
+                b'>' => {
+                    self.push(TokenKind::Punct('>'), self.pos);
+                    self.pos += 1;
+                    return false;
+                }
+                b'{' => {
+                    self.push(TokenKind::Punct('{'), self.pos);
+                    self.pos += 1;
+                    self.lex_script(true);
+                    if self.peek(0) == Some(b'}') {
+                        self.push(TokenKind::Punct('}'), self.pos);
+                        self.pos += 1;
+                    }
+                }
+                b'"' | b'\'' => self.lex_attribute_value(c, markup),
+                b'=' => {
+                    self.push(TokenKind::Punct('='), self.pos);
+                    self.pos += 1;
+                }
+                _ if is_ident_start(self.current_char()) || c == b':' || c == b'@' || c == b'#' => {
+                    let start = self.pos;
+                    while self.pos < self.bytes.len() {
+                        let ch = self.current_char();
+                        if is_ident_continue(ch) || matches!(ch, '-' | ':' | '.' | '@' | '#') {
+                            self.pos += ch.len_utf8();
+                        } else {
+                            break;
+                        }
+                    }
+                    let name = self.src[start..self.pos].to_string();
+                    self.push(TokenKind::Ident(name), start);
+                }
+                _ => self.pos += self.current_char().len_utf8().max(1),
+            }
+        }
+
+        true
+    }
+
+    /// Attribute values are literal strings; in templates they may also hold code (`:title="$t('key')"`)
+    fn lex_attribute_value(&mut self, quote: u8, markup: bool) {
+        let start = self.pos;
+        let end = self.src[start + 1..]
+            .find(quote as char)
+            .map_or(self.bytes.len(), |p| start + 1 + p);
+        let value = &self.src[start + 1..end];
+        self.push(TokenKind::Str(value.to_string()), start);
+        self.pos = (end + 1).min(self.bytes.len());
+
+        if markup && value.contains('(') {
+            let mut inner = Lexer {
+                src: value,
+                bytes: value.as_bytes(),
+                pos: 0,
+                offset: self.offset + start + 1,
+                jsx: false,
+                tokens: Vec::new(),
+                comments: Vec::new(),
+            };
+            inner.lex_script(false);
+            self.tokens.extend(inner.tokens);
+            self.comments.extend(inner.comments);
+        }
+    }
+
+    /// Lex JSX children: text is skipped, `{...}` is code, nested elements recurse
+    fn lex_children(&mut self, markup: bool) {
+        while self.pos < self.bytes.len() {
+            let next = self.src[self.pos..]
+                .find(['<', '{'])
+                .map_or(self.bytes.len(), |p| self.pos + p);
+            self.pos = next;
+
+            match self.peek(0) {
+                Some(b'{') => {
+                    self.push(TokenKind::Punct('{'), self.pos);
+                    self.pos += 1;
+                    self.lex_script(true);
+                    if self.peek(0) == Some(b'}') {
+                        self.push(TokenKind::Punct('}'), self.pos);
+                        self.pos += 1;
+                    }
+                }
+                Some(b'<') if self.src[self.pos..].starts_with("<!--") => {
+                    let start = self.pos;
+                    self.pos = self.src[self.pos..]
+                        .find("-->")
+                        .map_or(self.bytes.len(), |p| self.pos + p + 3);
+                    self.comments
+                        .push((start + self.offset, self.pos + self.offset));
+                }
+                Some(b'<') if self.peek(1) == Some(b'/') => {
+                    self.pos = self.src[self.pos..]
+                        .find('>')
+                        .map_or(self.bytes.len(), |p| self.pos + p + 1);
+                    if !markup {
+                        return;
+                    }
+                }
+                Some(b'<') if self.starts_element() => {
+                    if markup {
+                        self.lex_markup_element();
+                    } else {
+                        self.lex_jsx_element();
+                    }
+                }
+                Some(_) => self.pos += 1,
+                None => return,
+            }
+        }
+    }
+
+    /// Lex an HTML-like template. Elements are not nested: text between tags is simply skipped
+    fn lex_markup(&mut self) {
+        self.lex_children(true);
+    }
+
+    fn lex_markup_element(&mut self) {
+        let tag_start = self.pos;
+        let self_closing = self.lex_tag(true);
+        if self_closing {
+            return;
+        }
+
+        let name: String = self.src[tag_start + 1..]
+            .chars()
+            .take_while(|c| is_ident_continue(*c) || *c == '-')
+            .collect();
+        let name = name.to_ascii_lowercase();
+        let is_script = name == "script";
+        if !is_script && !RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+            return;
+        }
+
+        let closing = format!("</{}", name);
+        let body_end = find_ignore_case(&self.src[self.pos..], &closing)
+            .map_or(self.bytes.len(), |p| self.pos + p);
+
+        if is_script {
+            self.lex_embedded(self.pos, body_end, false);
+        }
+        self.pos = body_end;
+    }
+
+    /// Lex MDX. `import`/`export` blocks are script, fenced code blocks are skipped and the rest is
+    /// lexed like a template, so only JSX elements and `{ }` expressions are tokenized
+    fn lex_mdx(&mut self) {
+        let len = self.bytes.len();
+        let mut prose_start = 0;
+        let mut line_start = 0;
+
+        while line_start < len {
+            let line = &self.src[line_start..];
+            let fence = ["```", "~~~"]
+                .into_iter()
+                .find(|fence| line.trim_start().starts_with(fence));
+            let esm = line.starts_with("import ") || line.starts_with("export ");
+
+            if let Some(fence) = fence {
+                let mut end = line_end(self.src, line_start);
+                while end < len {
+                    let next = line_end(self.src, end);
+                    let closing = self.src[end..next].trim_start().starts_with(fence);
+                    end = next;
+                    if closing {
+                        break;
+                    }
+                }
+                self.lex_embedded(prose_start, line_start, true);
+                prose_start = end;
+                line_start = end;
+            } else if esm {
+                let end = self.src[line_start..]
+                    .find("\n\n")
+                    .map_or(len, |p| line_start + p + 1);
+                self.lex_embedded(prose_start, line_start, true);
+                self.lex_embedded(line_start, end, false);
+                prose_start = end;
+                line_start = end;
+            } else {
+                line_start = line_end(self.src, line_start);
+            }
+        }
+
+        self.lex_embedded(prose_start, len, true);
+    }
+
+    /// Lex `start..end` as a template (`markup`) or as script, keeping positions in this file
+    fn lex_embedded(&mut self, start: usize, end: usize, markup: bool) {
+        let body = &self.src[start..end];
+        let mut inner = Lexer {
+            src: body,
+            bytes: body.as_bytes(),
+            pos: 0,
+            offset: self.offset + start,
+            jsx: true,
+            tokens: Vec::new(),
+            comments: Vec::new(),
+        };
+        if markup {
+            inner.lex_markup();
+        } else {
+            inner.lex_script(false);
+        }
+        self.tokens.extend(inner.tokens);
+        self.comments.extend(inner.comments);
+    }
+}
+
+/// Offset just past the end of the line containing `at`
+fn line_end(src: &str, at: usize) -> usize {
+    src[at..].find('\n').map_or(src.len(), |p| at + p + 1)
+}
+
+/// Byte offset of an ASCII `needle` in `haystack`, ignoring ASCII case
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.as_bytes();
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle))
+}
+
+/// Decode an escape sequence starting at the backslash at `i`; returns the index after it
+fn read_escape(src: &str, i: usize, out: &mut String) -> usize {
+    let mut chars = src[i + 1..].chars();
+    let Some(c) = chars.next() else {
+        return src.len();
+    };
+    let next = i + 1 + c.len_utf8();
+
+    match c {
+        'n' => out.push('\n'),
+        't' => out.push('\t'),
+        'r' => out.push('\r'),
+        '0' => out.push('\0'),
+        'u' if src[next..].starts_with('{') => {
+            if let Some(end) = src[next..].find('}') {
+                if let Some(ch) = u32::from_str_radix(&src[next + 1..next + end], 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                {
+                    out.push(ch);
+                }
+                return next + end + 1;
+            }
+        }
+        'u' => {
+            if let Some(ch) = src
+                .get(next..next + 4)
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+            {
+                out.push(ch);
+                return next + 4;
+            }
+        }
+        '\n' => {}
+        _ => out.push(c),
+    }
+
+    next
+}
+
+/// Find the `}` closing an interpolation that starts at `start`, skipping nested code
+fn skip_balanced_braces(src: &str, start: usize) -> usize {
+    let mut lexer = Lexer {
+        src,
+        bytes: src.as_bytes(),
+        pos: start,
+        offset: 0,
+        jsx: false,
+        tokens: Vec::new(),
+        comments: Vec::new(),
+    };
+    lexer.lex_script(true);
+    lexer.pos
+}
+
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c == '$' || c.is_alphabetic()
+}
+
+fn is_ident_continue(c: char) -> bool {
+    c == '_' || c == '$' || c.is_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(source: &str, kind: SourceKind) -> Vec<KeyUsage> {
+        let call_sites = CallSites::new(&[], &[]).unwrap();
+        extract_usages(source, kind, &call_sites)
+            .into_iter()
+            .map(|u| u.key)
+            .collect()
+    }
+
+    fn literal(key: &str) -> KeyUsage {
+        KeyUsage::Literal(key.to_string())
+    }
+
+    #[test]
+    fn test_call_sites() {
+        let code = r#"
+const status = getStatus(); // t('comment.key')
+/* i18n.t("block.comment") */
+t('app.title');
+i18n.t("app.subtitle", { count });
+this.$t(`app.footer`);
+other('not.a.key');
+const label = 'status';
+t(['errors.specific', 'errors.fallback']);
+"#;
+        assert_eq!(
+            keys(code, SourceKind::Jsx),
+            vec![
+                literal("app.title"),
+                literal("app.subtitle"),
+                literal("app.footer"),
+                literal("errors.specific"),
+                literal("errors.fallback"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_template() {
+        let code = r#"
+t(`status.${item.status}`);
+t(`errors.${code}.title`);
+t('errors.' + code.toLowerCase() + '.body', { count });
+t(['menu', item.id, 'label'].join('.'));
+t(`${ns}:foo.bar`);
+t(key);
+t(`${a}.${b}`);
+"#;
+        let templates: Vec<String> = keys(code, SourceKind::Jsx)
+            .into_iter()
+            .map(|key| match key {
+                KeyUsage::Dynamic(template) => template.as_str().to_string(),
+                KeyUsage::Literal(key) => key,
+            })
+            .collect();
+        assert_eq!(
+            templates,
+            [
+                "status.*",
+                "errors.*.title",
+                "errors.*.body",
+                "menu.*.label",
+                "*:foo.bar"
+            ]
+        );
+
+        // Wildcards keep the expression they stand for
+        let KeyUsage::Dynamic(template) = &keys("t('status.' + item.status)", SourceKind::Jsx)[0]
+        else {
+            panic!("expected a template");
+        };
+        let narrowed = template
+            .narrow(|expression| (expression == "item.status").then(|| vec!["open".to_string()]))
+            .regex();
+        assert!(narrowed.is_match("status.open"));
+        assert!(!narrowed.is_match("status.closed"));
+    }
+
+    #[test]
+    fn test_jsx_components_and_text() {
+        let code = r#"
+const View = () => (
+  <div title="Don't">
+    It's <Trans i18nKey="welcome.message" /> {t('app.title')}
+    <FormattedMessage id={'app.id'} values={{ a: 1 }} />
+    <p>{/* t('commented.out') */}</p>
+  </div>
+);
+const re = /it's/g;
+t("after.regex");
+"#;
+        assert_eq!(
+            keys(code, SourceKind::Jsx),
+            vec![
+                literal("welcome.message"),
+                literal("app.title"),
+                literal("app.id"),
+                literal("after.regex"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markup_templates() {
+        let code = r#"<template>
+  <!-- {{ $t('old.key') }} -->
+  <p :title="$t('attr.key')">{{ $t('text.key') }} Don't</p>
+</template>
+<script setup lang="ts">
+const { t } = useI18n();
+const label = t('script.key');
+</script>"#;
+        assert_eq!(
+            keys(code, SourceKind::Markup),
+            vec![
+                literal("attr.key"),
+                literal("text.key"),
+                literal("script.key")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mdx() {
+        let code = r#"import { Trans } from 'react-i18next'
+export const title = t('esm.key')
+
+# Don't {t('heading.key')}
+
+{/* t('old.key') */}
+<!-- t('older.key') -->
+<Trans i18nKey="element.key" />
+
+```js
+t('example.key')
+```
+"#;
+        assert_eq!(
+            keys(code, SourceKind::Mdx),
+            vec![
+                literal("esm.key"),
+                literal("heading.key"),
+                literal("element.key")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_positions() {
+        let call_sites = CallSites::new(&[], &[]).unwrap();
+        let usages = extract_usages("const a = 1;\n  t('x.y');", SourceKind::Jsx, &call_sites);
+
+        assert_eq!(usages.len(), 1);
+        assert_eq!((usages[0].line, usages[0].column), (2, 5));
+    }
+
+    #[test]
+    fn test_namespace_binding() {
+        let call_sites = CallSites::new(&[], &[]).unwrap();
+        let code = r#"
+t('plain.key');
+const { t } = useTranslation(['billing', 'common']);
+t('invoice.title');
+t('common:app.title');
+t('app.name', { count: 2, ns: 'common' });
+<Trans i18nKey="invoice.note" ns="legal" />;
+const { t: tr } = useTranslation();
+t('back.to.default');
+const fixed = i18n.getFixedT(null, 'emails');
+"#;
+        let usages: Vec<(Option<String>, KeyUsage)> =
+            extract_usages(code, SourceKind::Jsx, &call_sites)
+                .into_iter()
+                .map(|u| (u.namespace, u.key))
+                .collect();
+        let ns = |n: &str| Some(n.to_string());
+
+        assert_eq!(
+            usages,
+            vec![
+                (None, literal("plain.key")),
+                (ns("billing"), literal("invoice.title")),
+                (ns("billing"), literal("common:app.title")),
+                (ns("common"), literal("app.name")),
+                (ns("legal"), literal("invoice.note")),
+                (None, literal("back.to.default")),
+            ]
+        );
+    }
+    #[test]
+    fn test_namespace_binding_is_scoped() {
+        let call_sites = CallSites::new(&[], &[]).unwrap();
+        let code = r#"
+export function Invoice() {
+  const { t } = useTranslation('billing');
+  return <p>{t('invoice.title')}</p>;
+}
+
+export function Settings() {
+  const { t } = useTranslation('account');
+  return <h1>{t('settings.title')}</h1>;
+}
+
+export const Profile = ({ t }) => <h2>{t('profile.title')}</h2>;
+
+t('after.components');
+"#;
+        let usages: Vec<(Option<String>, KeyUsage)> =
+            extract_usages(code, SourceKind::Jsx, &call_sites)
+                .into_iter()
+                .map(|u| (u.namespace, u.key))
+                .collect();
+
+        assert_eq!(
+            usages,
+            vec![
+                (Some("billing".to_string()), literal("invoice.title")),
+                (Some("account".to_string()), literal("settings.title")),
+                (None, literal("profile.title")),
+                (None, literal("after.components")),
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use std::collections::HashSet;
-use std::path::Path;
+use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ::ignore::Match;
+use std::path::{Component, Path, PathBuf};
 
 /// Default ignore patterns for common build and dependency directories
 const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
@@ -32,90 +33,230 @@ const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
     "*.snap",
 ];
 
-/// Compiled ignore patterns for efficient matching
+/// User-provided ignore rules. Patterns containing a slash are anchored at `root`
+pub struct IgnoreRules<'a> {
+    pub root: &'a Path,
+    pub exclude: &'a [String],
+    pub include: &'a [String],
+}
+
+/// Compiled gitignore-style patterns, highest precedence layer first
+#[derive(Clone)]
 pub struct IgnorePatterns {
-    exact_matches: HashSet<String>,
-    glob_regexes: Vec<regex::Regex>,
+    layers: Vec<Gitignore>,
+    /// Literal directories below which a re-include pattern may match
+    whitelist_roots: Vec<PathBuf>,
+    cwd: PathBuf,
 }
 
 impl IgnorePatterns {
-    /// Check if a file path should be ignored
-    pub fn should_ignore(&self, file_path: &str) -> bool {
-        let path = Path::new(file_path);
-        let path_str = file_path;
-        
-        // Fast path: check exact directory/file name matches first
-        for component in path.components() {
-            if let std::path::Component::Normal(name) = component {
-                let component_str = name.to_string_lossy();
-                if self.exact_matches.contains(component_str.as_ref()) {
-                    return true;
-                }
+    /// Check if a path should be ignored.
+    ///
+    /// The path itself is checked first, then each parent directory; the first
+    /// layer with a matching pattern decides, and within a layer the last matching
+    /// pattern wins (so `!pattern` re-includes).
+    pub fn should_ignore(&self, path: &Path, is_dir: bool) -> bool {
+        let mut current = Some(path);
+        let mut current_is_dir = is_dir;
+
+        while let Some(candidate) = current {
+            if is_walk_root(candidate) {
+                break;
             }
-        }
-        
-        // Check glob patterns (slower, but pre-compiled)
-        for pattern in &self.glob_regexes {
-            // Check filename first (most common case)
-            if let Some(file_name) = path.file_name() {
-                if pattern.is_match(&file_name.to_string_lossy()) {
-                    return true;
+
+            let absolute = normalize(&self.cwd.join(candidate));
+            for layer in &self.layers {
+                match layer.matched(&absolute, current_is_dir) {
+                    Match::Ignore(_) => return true,
+                    Match::Whitelist(_) => return false,
+                    Match::None => {}
                 }
             }
-            // Check full path as fallback
-            if pattern.is_match(path_str) {
-                return true;
-            }
+
+            current = candidate.parent();
+            current_is_dir = true;
         }
-        
+
         false
     }
+
+    /// Check if traversal should skip a directory entirely.
+    ///
+    /// Ignored directories are still entered when a re-include pattern points below them.
+    pub fn should_skip_dir(&self, path: &Path) -> bool {
+        if !self.should_ignore(path, true) {
+            return false;
+        }
+
+        let absolute = normalize(&self.cwd.join(path));
+        !self
+            .whitelist_roots
+            .iter()
+            .any(|root| root.starts_with(&absolute) || absolute.starts_with(root))
+    }
 }
 
-/// Load default ignore patterns
-pub fn load_ignore_patterns() -> IgnorePatterns {
-    let mut exact_matches = HashSet::new();
-    let mut glob_regexes = Vec::new();
-    
-    // Load default patterns
-    for pattern in DEFAULT_IGNORE_PATTERNS {
-        if pattern.contains('*') {
-            // Compile glob pattern to regex once
-            let regex_pattern = pattern
-                .replace(".", "\\.")
-                .replace("*", ".*");
-            if let Ok(re) = regex::Regex::new(&regex_pattern) {
-                glob_regexes.push(re);
+/// Whether a path has no components left that patterns could apply to
+fn is_walk_root(path: &Path) -> bool {
+    match path.components().next_back() {
+        None => true,
+        Some(Component::CurDir) | Some(Component::ParentDir) | Some(Component::RootDir) => true,
+        Some(Component::Prefix(_)) => true,
+        Some(Component::Normal(_)) => false,
+    }
+}
+
+/// Remove `.` components so paths can be compared with `starts_with`
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// Characters that start a glob in a pattern
+fn is_glob_component(component: &str) -> bool {
+    component.contains(['*', '?', '[', '{'])
+}
+
+/// Literal directory that an anchored re-include pattern lives under, if any
+fn whitelist_root(root: &Path, pattern: &str) -> Option<PathBuf> {
+    let pattern = pattern.trim_start_matches('/').trim_end_matches('/');
+    if !pattern.contains('/') {
+        // Unanchored patterns match by name at any depth; they cannot reach into ignored directories
+        return None;
+    }
+
+    let literal: PathBuf = pattern
+        .split('/')
+        .take_while(|component| !is_glob_component(component) && !component.is_empty())
+        .collect();
+    if literal.as_os_str().is_empty() {
+        return None;
+    }
+
+    Some(normalize(&root.join(literal)))
+}
+
+/// Compile default ignore patterns plus user rule sets (given highest precedence first)
+pub fn load_ignore_patterns(
+    rule_sets: &[IgnoreRules],
+) -> Result<IgnorePatterns, Box<dyn std::error::Error>> {
+    let cwd = std::env::current_dir()?;
+    let mut layers = Vec::new();
+    let mut whitelist_roots = Vec::new();
+
+    for rules in rule_sets {
+        let root = normalize(&cwd.join(rules.root));
+        let mut builder = GitignoreBuilder::new(&root);
+
+        for pattern in rules.exclude {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| format!("Invalid exclude pattern '{}': {}", pattern, e))?;
+            if let Some(negated) = pattern.strip_prefix('!') {
+                whitelist_roots.extend(whitelist_root(&root, negated));
             }
-        } else {
-            exact_matches.insert(pattern.to_string());
         }
+
+        for pattern in rules.include {
+            let pattern = pattern.trim_start_matches('!');
+            builder
+                .add_line(None, &format!("!{}", pattern))
+                .map_err(|e| format!("Invalid include pattern '{}': {}", pattern, e))?;
+            whitelist_roots.extend(whitelist_root(&root, pattern));
+        }
+
+        layers.push(builder.build()?);
     }
-    
-    IgnorePatterns {
-        exact_matches,
-        glob_regexes,
+
+    // Load default patterns as the lowest precedence layer
+    let mut defaults = GitignoreBuilder::new(&cwd);
+    for pattern in DEFAULT_IGNORE_PATTERNS {
+        defaults.add_line(None, pattern)?;
     }
+    layers.push(defaults.build()?);
+
+    Ok(IgnorePatterns {
+        layers,
+        whitelist_roots,
+        cwd,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    fn ignored(patterns: &IgnorePatterns, path: &str) -> bool {
+        patterns.should_ignore(Path::new(path), false)
+    }
+
+    fn rules<'a>(exclude: &'a [String], include: &'a [String]) -> IgnoreRules<'a> {
+        IgnoreRules {
+            root: Path::new("."),
+            exclude,
+            include,
+        }
+    }
+
     #[test]
     fn test_default_patterns() {
-        let patterns = load_ignore_patterns();
-        assert!(patterns.should_ignore("src/node_modules/foo.js"));
-        assert!(patterns.should_ignore(".git/config"));
+        let patterns = load_ignore_patterns(&[]).unwrap();
+        assert!(ignored(&patterns, "src/node_modules/foo.js"));
+        assert!(ignored(&patterns, ".git/config"));
     }
-    
+
     #[test]
     fn test_should_ignore_path() {
-        let patterns = load_ignore_patterns();
-        
-        assert!(patterns.should_ignore("src/node_modules/foo.js"));
-        assert!(patterns.should_ignore("app.log"));
-        assert!(!patterns.should_ignore("src/components/Button.tsx"));
+        let patterns = load_ignore_patterns(&[]).unwrap();
+
+        assert!(ignored(&patterns, "src/node_modules/foo.js"));
+        assert!(ignored(&patterns, "app.log"));
+        assert!(!ignored(&patterns, "src/components/Button.tsx"));
     }
-}
 
+    #[test]
+    fn test_glob_semantics() {
+        let exclude = vec![
+            "src/legacy/**".to_string(),
+            "**/*.stories.tsx".to_string(),
+            "src/v?/".to_string(),
+        ];
+        let patterns = load_ignore_patterns(&[rules(&exclude, &[])]).unwrap();
+
+        assert!(ignored(&patterns, "src/legacy/old/Page.tsx"));
+        assert!(!ignored(&patterns, "lib/src/legacy/Page.tsx"));
+        assert!(ignored(&patterns, "src/components/Button.stories.tsx"));
+        assert!(ignored(&patterns, "src/v1/index.ts"));
+        assert!(!ignored(&patterns, "src/v10/index.ts"));
+    }
+
+    #[test]
+    fn test_negation_and_include() {
+        let exclude = vec![
+            "src/legacy/**".to_string(),
+            "!src/legacy/keep.ts".to_string(),
+        ];
+        let include = vec!["test".to_string(), "tests/fixtures/**".to_string()];
+        let patterns = load_ignore_patterns(&[rules(&exclude, &include)]).unwrap();
+
+        assert!(ignored(&patterns, "src/legacy/old.ts"));
+        assert!(!ignored(&patterns, "src/legacy/keep.ts"));
+        assert!(!ignored(&patterns, "src/test/helpers.ts"));
+        assert!(!ignored(&patterns, "tests/fixtures/a.ts"));
+        assert!(ignored(&patterns, "tests/other.ts"));
+
+        // `tests` is ignored by default but must be entered to reach the fixtures
+        assert!(!patterns.should_skip_dir(Path::new("tests")));
+        assert!(patterns.should_skip_dir(Path::new("node_modules")));
+    }
+
+    #[test]
+    fn test_user_rules_override_defaults() {
+        let include = vec!["*.test.ts".to_string()];
+        let patterns = load_ignore_patterns(&[rules(&[], &include)]).unwrap();
+
+        assert!(!ignored(&patterns, "src/Button.test.ts"));
+        assert!(ignored(&patterns, "src/Button.spec.ts"));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::search::MissingKey;
+use crate::translation::{split_namespace, NAMESPACE_SEPARATOR};
+use colored::*;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, Write};
+
+/// Largest edit distance at which a missing key counts as a near miss of an unused key
+const NEAR_MISS_DISTANCE: usize = 2;
+
+/// What the user decided for the unused keys
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    /// Keys to remove from the translation files
+    pub delete: Vec<String>,
+    /// Keys left in place for now
+    pub keep: Vec<String>,
+    /// Patterns to add to the config keep-list
+    pub keep_patterns: Vec<String>,
+}
+
+/// A locale name and its translations
+pub type LocaleTranslations = (String, HashMap<String, Value>);
+
+/// Everything shown next to an unused key while reviewing it
+pub struct ReviewContext<'a> {
+    /// Translations of every locale, by locale name
+    pub locales: &'a [LocaleTranslations],
+    /// Keys used at translation call sites that have no translation
+    pub missing_keys: &'a [MissingKey],
+}
+
+enum Choice {
+    Delete,
+    Keep,
+    AddToKeepList,
+    Review,
+    Quit,
+}
+
+/// Walk through the unused keys grouped by namespace and first segment, asking what to do with
+/// each group or key. Keys not decided on (quit or end of input) are kept
+pub fn review(
+    unused_keys: &[String],
+    context: &ReviewContext,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<Selection> {
+    let mut selection = Selection::default();
+    let groups = group_keys(unused_keys);
+    let total = groups.len();
+    let mut remaining = groups.into_iter().enumerate();
+
+    while let Some((i, (group, keys))) = remaining.next() {
+        writeln!(
+            output,
+            "\n{} {} ({} of {}, {} keys)",
+            "▸".cyan(),
+            group_label(&group).bold(),
+            i + 1,
+            total,
+            keys.len()
+        )?;
+        for key in &keys {
+            describe_key(key, context, &mut output)?;
+        }
+
+        let patterns = group_patterns(&group, &keys);
+        let choice = if keys.len() == 1 {
+            let prompt = format!(
+                "[d]elete, [k]eep, [a]dd {} to keep list, [q]uit",
+                patterns.join(", ")
+            );
+            ask(&mut input, &mut output, &prompt, false)?
+        } else {
+            let prompt = format!(
+                "[d]elete all, [k]eep all, [a]dd {} to keep list, [r]eview each, [q]uit",
+                patterns.join(", ")
+            );
+            ask(&mut input, &mut output, &prompt, true)?
+        };
+
+        match choice {
+            Choice::Delete => selection.delete.extend(keys),
+            Choice::Keep => selection.keep.extend(keys),
+            Choice::AddToKeepList => {
+                selection.keep_patterns.extend(patterns);
+                selection.keep.extend(keys);
+            }
+            Choice::Review => {
+                let mut keys = keys.into_iter();
+                while let Some(key) = keys.next() {
+                    writeln!(output)?;
+                    describe_key(&key, context, &mut output)?;
+                    let prompt = format!("[d]elete, [k]eep, [a]dd {} to keep list, [q]uit", key);
+                    match ask(&mut input, &mut output, &prompt, false)? {
+                        Choice::Delete => selection.delete.push(key),
+                        Choice::AddToKeepList => {
+                            selection.keep_patterns.push(key.clone());
+                            selection.keep.push(key);
+                        }
+                        Choice::Quit => {
+                            selection.keep.push(key);
+                            selection.keep.extend(keys);
+                            selection
+                                .keep
+                                .extend(remaining.flat_map(|(_, (_, keys))| keys));
+                            return Ok(selection);
+                        }
+                        Choice::Keep | Choice::Review => selection.keep.push(key),
+                    }
+                }
+            }
+            Choice::Quit => {
+                selection.keep.extend(keys);
+                selection
+                    .keep
+                    .extend(remaining.flat_map(|(_, (_, keys))| keys));
+                return Ok(selection);
+            }
+        }
+    }
+
+    Ok(selection)
+}
+
+/// Ask until a valid answer is given; end of input counts as quit
+fn ask(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+    allow_review: bool,
+) -> io::Result<Choice> {
+    loop {
+        write!(output, "{} {} ", prompt, ">".cyan())?;
+        output.flush()?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            writeln!(output)?;
+            return Ok(Choice::Quit);
+        }
+
+        match answer.trim().to_lowercase().as_str() {
+            "d" | "delete" => return Ok(Choice::Delete),
+            "k" | "keep" => return Ok(Choice::Keep),
+            "a" | "add" => return Ok(Choice::AddToKeepList),
+            "r" | "review" if allow_review => return Ok(Choice::Review),
+            "q" | "quit" => return Ok(Choice::Quit),
+            _ => {}
+        }
+    }
+}
+
+/// Print a key with its value in every locale and any near-miss usages
+fn describe_key(key: &str, context: &ReviewContext, output: &mut impl Write) -> io::Result<()> {
+    writeln!(output, "  {}", key.yellow())?;
+
+    for (locale, translations) in context.locales {
+        let value = match translations.get(key) {
+            Some(value) => value.to_string(),
+            None => "(missing)".dimmed().to_string(),
+        };
+        writeln!(output, "    {} {}", format!("{}:", locale).dimmed(), value)?;
+    }
+
+    for missing in near_misses(key, context.missing_keys) {
+        writeln!(
+            output,
+            "    {} '{}' at {}:{}:{}",
+            "near miss:".magenta(),
+            missing.key,
+            missing.file,
+            missing.line,
+            missing.column
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Missing keys that look like a typo of `key`
+fn near_misses<'a>(key: &str, missing_keys: &'a [MissingKey]) -> Vec<&'a MissingKey> {
+    missing_keys
+        .iter()
+        .filter(|missing| edit_distance(key, &missing.key) <= NEAR_MISS_DISTANCE)
+        .collect()
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Group keys by namespace and first key segment (`common:app.title` -> `common:app`).
+/// Top-level keys share the group of their namespace
+fn group_keys(keys: &[String]) -> BTreeMap<String, Vec<String>> {
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for key in keys {
+        groups.entry(group_of(key)).or_default().push(key.clone());
+    }
+    for keys in groups.values_mut() {
+        keys.sort();
+    }
+    groups
+}
+
+fn group_of(key: &str) -> String {
+    let (namespace, rest) = split_namespace(key);
+    let first = match rest.split_once('.') {
+        Some((first, _)) => first,
+        None => "",
+    };
+    match namespace {
+        Some(namespace) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, first),
+        None => first.to_string(),
+    }
+}
+
+fn group_label(group: &str) -> String {
+    if group.is_empty() || group.ends_with(NAMESPACE_SEPARATOR) {
+        format!("{}(top level)", group)
+    } else {
+        group.to_string()
+    }
+}
+
+/// Keep-list entries covering a whole group: `group.*`, or the keys themselves for top-level keys
+fn group_patterns(group: &str, keys: &[String]) -> Vec<String> {
+    if group.is_empty() || group.ends_with(NAMESPACE_SEPARATOR) {
+        keys.to_vec()
+    } else {
+        vec![format!("{}.*", group)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_groups_and_keys() {
+        let unused: Vec<String> = ["app.old", "app.legacy", "errors.a", "errors.b", "misc.x"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        let locales = vec![(
+            "en".to_string(),
+            HashMap::from([("app.old".to_string(), Value::from("Old"))]),
+        )];
+        let missing = vec![MissingKey {
+            key: "app.olf".to_string(),
+            file: "src/App.tsx".to_string(),
+            line: 4,
+            column: 9,
+        }];
+        let context = ReviewContext {
+            locales: &locales,
+            missing_keys: &missing,
+        };
+
+        // app: review each (delete legacy, keep old); errors: add to keep list; misc: nonsense then delete
+        let input = "r\nd\nk\na\nwhat\nd\n";
+        let mut output = Vec::new();
+        let selection = review(&unused, &context, input.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(selection.delete, ["app.legacy", "misc.x"]);
+        assert_eq!(selection.keep, ["app.old", "errors.a", "errors.b"]);
+        assert_eq!(selection.keep_patterns, ["errors.*"]);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\"Old\""));
+        assert!(output.contains("'app.olf' at src/App.tsx:4:9"));
+
+        // Quitting keeps everything not decided yet
+        let selection = review(&unused, &context, "d\nq\n".as_bytes(), Vec::new()).unwrap();
+        assert_eq!(selection.delete, ["app.legacy", "app.old"]);
+        assert_eq!(selection.keep.len(), 3);
+    }
+}
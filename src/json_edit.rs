@@ -0,0 +1,611 @@
+use crate::json_spans::{Node, NodeKind};
+use serde::{Deserialize, Serialize};
+use serde_json::ser::{PrettyFormatter, Serializer};
+use serde_json::{Map, Value};
+use std::ops::Range;
+
+/// One step from a container to one of its entries: the member name (none for array elements)
+/// and the position of the entry in the container
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathSegment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub index: usize,
+}
+
+/// An entry cut out of the document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedEntry {
+    /// Flattened path (`a.b`, `a[0]`)
+    pub path: String,
+    pub segments: Vec<PathSegment>,
+    /// Span of the removed value in the original source
+    pub value: Range<usize>,
+}
+
+/// Result of `remove_entries`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub text: String,
+    /// Entries removed because `should_remove` asked for it, in document order
+    pub removed: Vec<RemovedEntry>,
+    /// Array elements `should_remove` asked for that stay, because removing them would shift a
+    /// later element that is referenced by its index
+    pub kept: Vec<String>,
+}
+
+/// Remove object members and array elements from a JSON document without reformatting it.
+///
+/// `should_remove` is asked about every member and element by its flattened path (`a.b`, `a[0]`).
+/// Array elements go wherever they are, unless `is_indexed` says a later element of the same array
+/// is referenced by its index: the elements before it then stay so it keeps its index. With
+/// `prune_empty`, objects and arrays emptied by the removal are removed as well (the root
+/// stays). Only the removed entries and their separators change; indentation, key order, escapes,
+/// line endings and whatever surrounds the root value are kept byte for byte.
+pub fn remove_entries(
+    source: &str,
+    root: &Node,
+    should_remove: &dyn Fn(&str) -> bool,
+    is_indexed: &dyn Fn(&str) -> bool,
+    prune_empty: bool,
+) -> Edit {
+    let editor = Editor {
+        source,
+        should_remove,
+        is_indexed,
+        prune_empty,
+    };
+
+    let mut removed = Vec::new();
+    let mut kept = Vec::new();
+    let mut text = String::with_capacity(source.len());
+    text.push_str(&source[..root.span.start]);
+    text.push_str(&editor.render(root, "", &[], &mut removed, &mut kept).text);
+    text.push_str(&source[root.span.end..]);
+    Edit {
+        text,
+        removed,
+        kept,
+    }
+}
+
+struct Editor<'a> {
+    source: &'a str,
+    should_remove: &'a dyn Fn(&str) -> bool,
+    is_indexed: &'a dyn Fn(&str) -> bool,
+    prune_empty: bool,
+}
+
+/// Rendered value, and whether it is a container that lost all of its entries
+struct Rendered {
+    text: String,
+    emptied: bool,
+}
+
+/// An entry of a container: where it starts (name or value) and its value
+struct Entry<'a> {
+    start: usize,
+    path: String,
+    segment: PathSegment,
+    value: &'a Node,
+}
+
+impl Editor<'_> {
+    fn render(
+        &self,
+        node: &Node,
+        path: &str,
+        segments: &[PathSegment],
+        removed: &mut Vec<RemovedEntry>,
+        kept: &mut Vec<String>,
+    ) -> Rendered {
+        let source = self.source;
+        let entries = match entries(node, path) {
+            Some(entries) => entries,
+            None => {
+                return Rendered {
+                    text: source[node.span.clone()].to_string(),
+                    emptied: false,
+                }
+            }
+        };
+
+        // Text of every entry, whether it stays, and what removing it (or inside it) cut out
+        let mut texts: Vec<String> = Vec::with_capacity(entries.len());
+        let mut keep: Vec<bool> = Vec::with_capacity(entries.len());
+        let mut entry_removed: Vec<Vec<RemovedEntry>> = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let mut entry_segments = segments.to_vec();
+            entry_segments.push(entry.segment.clone());
+
+            if (self.should_remove)(&entry.path) {
+                texts.push(source[entry.value.span.clone()].to_string());
+                keep.push(false);
+                entry_removed.push(vec![RemovedEntry {
+                    path: entry.path.clone(),
+                    segments: entry_segments,
+                    value: entry.value.span.clone(),
+                }]);
+                continue;
+            }
+            let mut child_removed = Vec::new();
+            let child = self.render(
+                entry.value,
+                &entry.path,
+                &entry_segments,
+                &mut child_removed,
+                kept,
+            );
+            texts.push(child.text);
+            keep.push(!(child.emptied && self.prune_empty));
+            entry_removed.push(child_removed);
+        }
+
+        // Removing an element before one referenced by its index would shift that one to another
+        // index, so the elements before the last indexed one stay
+        if matches!(node.kind, NodeKind::Array(_)) {
+            let pinned = entries
+                .iter()
+                .rposition(|entry| (self.is_indexed)(&entry.path))
+                .map_or(0, |i| i + 1);
+            for i in 0..pinned {
+                if !keep[i] {
+                    keep[i] = true;
+                    let before = entry_removed[i].len();
+                    entry_removed[i].retain(|entry| entry.path != entries[i].path);
+                    if entry_removed[i].len() < before {
+                        kept.push(entries[i].path.clone());
+                    }
+                }
+            }
+        }
+
+        let rendered: Vec<Option<String>> = texts
+            .into_iter()
+            .zip(&keep)
+            .map(|(text, kept)| kept.then_some(text))
+            .collect();
+        removed.extend(entry_removed.into_iter().flatten());
+
+        let unchanged = entries.iter().zip(&rendered).all(|(entry, rendered)| {
+            rendered.as_deref() == Some(&source[entry.value.span.clone()])
+        });
+        if unchanged {
+            return Rendered {
+                text: source[node.span.clone()].to_string(),
+                emptied: false,
+            };
+        }
+
+        let open = node.span.start;
+        let close = node.span.end - 1;
+        let mut out = String::new();
+        out.push_str(&source[open..open + 1]);
+
+        let kept: Vec<usize> = (0..entries.len())
+            .filter(|&i| rendered[i].is_some())
+            .collect();
+        for (n, &i) in kept.iter().enumerate() {
+            let entry = &entries[i];
+            // Whitespace before the entry, from the slot it moves into
+            out.push_str(&source[leading_range(source, &entries, n, open)]);
+            out.push_str(&source[entry.start..entry.value.span.start]);
+            out.push_str(rendered[i].as_deref().unwrap_or_default());
+            if n + 1 < kept.len() {
+                out.push_str(&source[separator_range(source, entry.value.span.end)]);
+            }
+        }
+
+        if let (Some(last), false) = (entries.last(), kept.is_empty()) {
+            // Whitespace before the closing bracket
+            out.push_str(&source[last.value.span.end..close]);
+        }
+
+        out.push_str(&source[close..node.span.end]);
+        Rendered {
+            text: out,
+            emptied: kept.is_empty(),
+        }
+    }
+}
+
+/// Entries of an object or array, None for scalars
+fn entries<'a>(node: &'a Node, path: &str) -> Option<Vec<Entry<'a>>> {
+    match &node.kind {
+        NodeKind::Scalar => None,
+        NodeKind::Object(members) => Some(
+            members
+                .iter()
+                .enumerate()
+                .map(|(i, member)| Entry {
+                    start: member.name_span.start,
+                    path: if path.is_empty() {
+                        member.name.clone()
+                    } else {
+                        format!("{}.{}", path, member.name)
+                    },
+                    segment: PathSegment {
+                        key: Some(member.name.clone()),
+                        index: i,
+                    },
+                    value: &member.value,
+                })
+                .collect(),
+        ),
+        NodeKind::Array(items) => Some(
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| Entry {
+                    start: item.span.start,
+                    path: format!("{}[{}]", path, i),
+                    segment: PathSegment {
+                        key: None,
+                        index: i,
+                    },
+                    value: item,
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Insert `value` at the path given by `segments`, creating missing objects and arrays along the
+/// way. Each new entry goes to its recorded index (or the end when the container is now shorter)
+/// and copies the indentation and separators of its siblings. Returns None when an object member
+/// already exists at the path
+pub fn insert_entry(
+    source: &str,
+    root: &Node,
+    segments: &[PathSegment],
+    value: &Value,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let unit = indent_unit(source, root);
+    let mut node = root;
+
+    for (depth, segment) in segments.iter().enumerate() {
+        let is_last = depth + 1 == segments.len();
+        let child = match (&node.kind, &segment.key) {
+            (NodeKind::Object(members), Some(name)) => members
+                .iter()
+                .find(|member| &member.name == name)
+                .map(|member| &member.value),
+            // Array elements are inserted, never matched, unless they lead further down
+            (NodeKind::Array(items), None) if !is_last => items
+                .get(segment.index)
+                .filter(|item| fits(item, &segments[depth + 1])),
+            (NodeKind::Array(_), None) => None,
+            _ => return Err("the path conflicts with the current structure".into()),
+        };
+
+        match child {
+            Some(_) if is_last => return Ok(None),
+            Some(child) => node = child,
+            None => {
+                let value = wrap(value, &segments[depth + 1..]);
+                let (range, text) = insertion(source, node, segment, &value, &unit);
+                let mut out = String::with_capacity(source.len() + text.len());
+                out.push_str(&source[..range.start]);
+                out.push_str(&text);
+                out.push_str(&source[range.end..]);
+                return Ok(Some(out));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether a node can hold the entry described by a segment
+fn fits(node: &Node, segment: &PathSegment) -> bool {
+    match node.kind {
+        NodeKind::Object(_) => segment.key.is_some(),
+        NodeKind::Array(_) => segment.key.is_none(),
+        NodeKind::Scalar => false,
+    }
+}
+
+/// Nest a value in the objects and arrays named by the remaining segments
+fn wrap(value: &Value, segments: &[PathSegment]) -> Value {
+    segments
+        .iter()
+        .rev()
+        .fold(value.clone(), |value, segment| match &segment.key {
+            Some(name) => {
+                let mut object = Map::new();
+                object.insert(name.clone(), value);
+                Value::Object(object)
+            }
+            None => Value::Array(vec![value]),
+        })
+}
+
+/// Range to replace and the text of a new entry of `container`
+fn insertion(
+    source: &str,
+    container: &Node,
+    segment: &PathSegment,
+    value: &Value,
+    unit: &str,
+) -> (Range<usize>, String) {
+    let open = container.span.start;
+    let close = container.span.end - 1;
+    let entries = entries(container, "").unwrap_or_default();
+    let newline = if source.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    if entries.is_empty() {
+        let multiline = source.trim_end().contains('\n');
+        if !multiline {
+            let entry = entry_text(segment, ": ", value, None, unit, newline);
+            return (open + 1..close, entry);
+        }
+        let line_indent = line_indent(source, open);
+        let indent = format!("{}{}", line_indent, unit);
+        let entry = entry_text(segment, ": ", value, Some(&indent), unit, newline);
+        return (
+            open + 1..close,
+            format!("{}{}{}{}{}", newline, indent, entry, newline, line_indent),
+        );
+    }
+
+    // Whitespace between two entries; the first one may follow the bracket directly
+    let gap = match entries.len() {
+        1 => {
+            let first = &source[leading_range(source, &entries, 0, open)];
+            if first.contains('\n') {
+                first
+            } else {
+                " "
+            }
+        }
+        _ => &source[leading_range(source, &entries, 1, open)],
+    };
+    let indent = gap.rfind('\n').map(|newline_at| &gap[newline_at + 1..]);
+    let colon = match &container.kind {
+        NodeKind::Object(members) => &source[members[0].name_span.end..members[0].value.span.start],
+        _ => "",
+    };
+    let entry = entry_text(segment, colon, value, indent, unit, newline);
+
+    match entries.get(segment.index) {
+        Some(next) => (next.start..next.start, format!("{},{}", entry, gap)),
+        None => {
+            let at = entries[entries.len() - 1].value.span.end;
+            (at..at, format!(",{}{}", gap, entry))
+        }
+    }
+}
+
+/// `"name": value` for object members, the value alone for array elements. Values spread over
+/// several lines when the entry is on a line of its own (`indent`)
+fn entry_text(
+    segment: &PathSegment,
+    colon: &str,
+    value: &Value,
+    indent: Option<&str>,
+    unit: &str,
+    newline: &str,
+) -> String {
+    let value = match indent {
+        Some(indent) => {
+            let mut buffer = Vec::new();
+            let formatter = PrettyFormatter::with_indent(unit.as_bytes());
+            let mut serializer = Serializer::with_formatter(&mut buffer, formatter);
+            value
+                .serialize(&mut serializer)
+                .expect("serializing a JSON value cannot fail");
+            String::from_utf8(buffer)
+                .expect("serde_json writes UTF-8")
+                .replace('\n', &format!("{}{}", newline, indent))
+        }
+        None => value.to_string(),
+    };
+
+    match &segment.key {
+        Some(name) => format!("{}{}{}", Value::from(name.as_str()), colon, value),
+        None => value,
+    }
+}
+
+/// Indentation of one nesting level, taken from the first entry of the root
+fn indent_unit(source: &str, root: &Node) -> String {
+    entries(root, "")
+        .filter(|entries| !entries.is_empty())
+        .and_then(|entries| {
+            let leading = &source[leading_range(source, &entries, 0, root.span.start)];
+            leading
+                .rfind('\n')
+                .map(|newline_at| leading[newline_at + 1..].to_string())
+        })
+        .filter(|unit| !unit.is_empty())
+        .unwrap_or_else(|| "  ".to_string())
+}
+
+/// Whitespace at the start of the line containing `position`
+fn line_indent(source: &str, position: usize) -> &str {
+    let line_start = source[..position].rfind('\n').map_or(0, |at| at + 1);
+    let line = &source[line_start..position];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Whitespace between the previous separator (or opening bracket) and an entry
+fn leading_range(source: &str, entries: &[Entry], i: usize, open: usize) -> Range<usize> {
+    let from = if i == 0 {
+        open + 1
+    } else {
+        separator_range(source, entries[i - 1].value.span.end).end
+    };
+    from..entries[i].start
+}
+
+/// Range from the end of a value through the comma that follows it
+fn separator_range(source: &str, value_end: usize) -> Range<usize> {
+    let comma = source[value_end..]
+        .find(',')
+        .map_or(value_end, |offset| value_end + offset + 1);
+    value_end..comma
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_spans::parse;
+
+    fn remove(source: &str, paths: &[&str]) -> String {
+        let root = parse(source).unwrap();
+        remove_entries(
+            source,
+            &root,
+            &|path| paths.contains(&path),
+            &|_| false,
+            false,
+        )
+        .text
+    }
+
+    #[test]
+    fn test_remove_entries() {
+        assert_eq!(
+            remove("{\"a\":1,\"b\":2,\"c\":3}", &["b"]),
+            "{\"a\":1,\"c\":3}"
+        );
+        assert_eq!(
+            remove("{\"a\":1,\"b\":2,\"c\":3}", &["a", "c"]),
+            "{\"b\":2}"
+        );
+        assert_eq!(remove("[1, 2, 3]\n", &["[2]"]), "[1, 2]\n");
+        assert_eq!(remove("{\"x\": {\"a\": 1}}", &["x.a"]), "{\"x\": {}}");
+        assert_eq!(
+            remove(
+                "{\n  \"list\": [\n    \"a\",\n    \"b\"\n  ]\n}",
+                &["list[1]"]
+            ),
+            "{\n  \"list\": [\n    \"a\"\n  ]\n}"
+        );
+        assert_eq!(remove("[1, 2, 3]", &["[0]", "[2]"]), "[2]");
+        assert_eq!(remove("[1, 2, 3]", &["[0]", "[1]", "[2]"]), "[]");
+
+        // Elements before one referenced by its index stay, so it keeps its index
+        let root = parse("[1, 2, 3, 4]").unwrap();
+        let edit = remove_entries(
+            "[1, 2, 3, 4]",
+            &root,
+            &|path| ["[0]", "[2]", "[3]"].contains(&path),
+            &|path| path == "[1]",
+            false,
+        );
+        assert_eq!(edit.text, "[1, 2]");
+        assert_eq!(edit.kept, ["[0]"]);
+        assert_eq!(edit.removed.len(), 2);
+        let untouched = "{ \"a\" : [ 1 ,2 ] }";
+        assert_eq!(remove(untouched, &[]), untouched);
+    }
+
+    #[test]
+    fn test_prune_emptied_containers() {
+        let source = "{\n  \"settings\": {\n    \"legacy\": {\n      \"a\": 1\n    },\n    \"list\": [{ \"b\": 2 }],\n    \"empty\": {}\n  },\n  \"title\": \"T\"\n}\n";
+        let root = parse(source).unwrap();
+        let unused = ["settings.legacy.a", "settings.list[0].b"];
+        let cleaned = remove_entries(
+            source,
+            &root,
+            &|path| unused.contains(&path),
+            &|_| false,
+            true,
+        );
+        let removed: Vec<&str> = cleaned
+            .removed
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect();
+
+        assert_eq!(removed, unused);
+        assert_eq!(
+            cleaned.removed[0].segments[1],
+            PathSegment {
+                key: Some("legacy".to_string()),
+                index: 0
+            }
+        );
+        assert_eq!(
+            cleaned.text,
+            "{\n  \"settings\": {\n    \"empty\": {}\n  },\n  \"title\": \"T\"\n}\n"
+        );
+
+        let root = parse("{\"a\": {\"b\": 1}}").unwrap();
+        assert_eq!(
+            remove_entries(
+                "{\"a\": {\"b\": 1}}",
+                &root,
+                &|path| path == "a.b",
+                &|_| false,
+                true
+            )
+            .text,
+            "{}"
+        );
+    }
+
+    #[test]
+    fn test_insert_entry_restores_removed_entries() {
+        let original = "{\n\t\"settings\": {\n\t\t\"legacy\": {\n\t\t\t\"a\": \"A\",\n\t\t\t\"b\": [1, 2]\n\t\t},\n\t\t\"theme\": \"T\"\n\t},\n\t\"list\": [\"x\", \"y\"]\n}\n";
+        let root = parse(original).unwrap();
+        let unused = ["settings.legacy.a", "settings.legacy.b", "list[1]"];
+        let edit = remove_entries(
+            original,
+            &root,
+            &|path| unused.contains(&path),
+            &|_| false,
+            true,
+        );
+        assert_eq!(
+            edit.text,
+            "{\n\t\"settings\": {\n\t\t\"theme\": \"T\"\n\t},\n\t\"list\": [\"x\"]\n}\n"
+        );
+
+        let mut restored = edit.text.clone();
+        for entry in &edit.removed {
+            let value: Value = serde_json::from_str(&original[entry.value.clone()]).unwrap();
+            let root = parse(&restored).unwrap();
+            restored = insert_entry(&restored, &root, &entry.segments, &value)
+                .unwrap()
+                .unwrap();
+        }
+        assert_eq!(
+            restored,
+            "{\n\t\"settings\": {\n\t\t\"legacy\": {\n\t\t\t\"a\": \"A\",\n\t\t\t\"b\": [\n\t\t\t\t1,\n\t\t\t\t2\n\t\t\t]\n\t\t},\n\t\t\"theme\": \"T\"\n\t},\n\t\"list\": [\"x\", \"y\"]\n}\n"
+        );
+
+        // Members that exist already are left alone
+        let root = parse(&restored).unwrap();
+        let entry = &edit.removed[0];
+        assert_eq!(
+            insert_entry(&restored, &root, &entry.segments, &Value::from("A")).unwrap(),
+            None
+        );
+    }
+    #[test]
+    fn test_insert_entry_into_empty_root() {
+        let segments = [
+            PathSegment {
+                key: Some("app".to_string()),
+                index: 0,
+            },
+            PathSegment {
+                key: Some("title".to_string()),
+                index: 0,
+            },
+        ];
+        let value = Value::from("T");
+
+        let root = parse("{\n}\n").unwrap();
+        assert_eq!(
+            insert_entry("{\n}\n", &root, &segments, &value).unwrap(),
+            Some("{\n  \"app\": {\n    \"title\": \"T\"\n  }\n}\n".to_string())
+        );
+    }
+}
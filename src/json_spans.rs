@@ -0,0 +1,352 @@
+use crate::extract::LineIndex;
+use crate::translation::ArrayMode;
+use std::ops::Range;
+
+/// A parsed JSON value with the byte range it occupies in the source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub span: Range<usize>,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeKind {
+    Object(Vec<Member>),
+    Array(Vec<Node>),
+    /// String, number, boolean or null
+    Scalar,
+}
+
+/// An object member: its decoded name, the span of the quoted name, and its value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub name: String,
+    pub name_span: Range<usize>,
+    pub value: Node,
+}
+
+/// Where a flattened key lives in the source: its property name (or array element) and its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySpan {
+    /// Start of the quoted property name, or of the value for array elements
+    pub start: usize,
+    pub value: Range<usize>,
+}
+
+/// Parse a JSON document, keeping the position of every value
+pub fn parse(source: &str) -> Result<Node, Box<dyn std::error::Error>> {
+    let mut parser = Parser {
+        source,
+        bytes: source.as_bytes(),
+        pos: 0,
+    };
+    if source.starts_with('\u{feff}') {
+        parser.pos = '\u{feff}'.len_utf8();
+    }
+
+    let node = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.bytes.len() {
+        return Err(parser.error("trailing characters after the JSON document"));
+    }
+    Ok(node)
+}
+
+/// Flattened keys with their spans: `a.b` for object members, `a[0]` for array elements
+pub fn flatten_spans(node: &Node, arrays: ArrayMode) -> Vec<(String, KeySpan)> {
+    let mut spans = Vec::new();
+    flatten_into(node, String::new(), node.span.start, arrays, &mut spans);
+    spans
+}
+
+fn flatten_into(
+    node: &Node,
+    prefix: String,
+    start: usize,
+    arrays: ArrayMode,
+    spans: &mut Vec<(String, KeySpan)>,
+) {
+    match &node.kind {
+        NodeKind::Array(_) if arrays == ArrayMode::Atomic && !prefix.is_empty() => spans.push((
+            prefix,
+            KeySpan {
+                start,
+                value: node.span.clone(),
+            },
+        )),
+        NodeKind::Object(members) => {
+            for member in members {
+                let key = if prefix.is_empty() {
+                    member.name.clone()
+                } else {
+                    format!("{}.{}", prefix, member.name)
+                };
+                flatten_into(&member.value, key, member.name_span.start, arrays, spans);
+            }
+        }
+        NodeKind::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten_into(
+                    item,
+                    format!("{}[{}]", prefix, i),
+                    item.span.start,
+                    arrays,
+                    spans,
+                );
+            }
+        }
+        NodeKind::Scalar => spans.push((
+            prefix,
+            KeySpan {
+                start,
+                value: node.span.clone(),
+            },
+        )),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Box<dyn std::error::Error> {
+        let (line, column) = LineIndex::new(self.source).position(self.pos.min(self.source.len()));
+        format!("{} at line {} column {}", message, line, column).into()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Box<dyn std::error::Error>> {
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, Box<dyn std::error::Error>> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let kind = match self.bytes.get(self.pos) {
+            Some(b'{') => self.object()?,
+            Some(b'[') => self.array()?,
+            Some(b'"') => {
+                self.string()?;
+                NodeKind::Scalar
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                self.number()?;
+                NodeKind::Scalar
+            }
+            Some(b't') => self.literal("true")?,
+            Some(b'f') => self.literal("false")?,
+            Some(b'n') => self.literal("null")?,
+            Some(_) => return Err(self.error("expected a JSON value")),
+            None => return Err(self.error("unexpected end of input")),
+        };
+
+        Ok(Node {
+            span: start..self.pos,
+            kind,
+        })
+    }
+
+    fn object(&mut self) -> Result<NodeKind, Box<dyn std::error::Error>> {
+        self.pos += 1;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(NodeKind::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return Err(self.error("expected a property name"));
+            }
+            let name_start = self.pos;
+            let name = self.string()?;
+            let name_span = name_start..self.pos;
+            self.expect(b':')?;
+            let value = self.value()?;
+            members.push(Member {
+                name,
+                name_span,
+                value,
+            });
+
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(NodeKind::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<NodeKind, Box<dyn std::error::Error>> {
+        self.pos += 1;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(NodeKind::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+
+            self.skip_whitespace();
+            match self.bytes.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(NodeKind::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    /// Parse a string literal and return its decoded content
+    fn string(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        self.pos += 1;
+        let mut decoded = String::new();
+
+        loop {
+            let Some(&byte) = self.bytes.get(self.pos) else {
+                return Err(self.error("unterminated string"));
+            };
+            match byte {
+                b'"' => {
+                    self.pos += 1;
+                    return Ok(decoded);
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    let escaped = match self.bytes.get(self.pos) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    decoded.push(escaped);
+                    self.pos += 1;
+                }
+                _ => {
+                    let c = self.source[self.pos..].chars().next().unwrap_or('\0');
+                    decoded.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    /// Decode `\uXXXX` (and a following low surrogate); leaves `pos` on the last hex digit
+    fn unicode_escape(&mut self) -> Result<char, Box<dyn std::error::Error>> {
+        let high = self.hex4(self.pos + 1)?;
+        self.pos += 4;
+
+        if (0xD800..0xDC00).contains(&high) && self.source[self.pos + 1..].starts_with("\\u") {
+            let low = self.hex4(self.pos + 3)?;
+            if (0xDC00..0xE000).contains(&low) {
+                self.pos += 6;
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return Ok(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+        }
+
+        Ok(char::from_u32(high).unwrap_or('\u{fffd}'))
+    }
+
+    fn hex4(&self, at: usize) -> Result<u32, Box<dyn std::error::Error>> {
+        self.source
+            .get(at..at + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn number(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+        {
+            self.pos += 1;
+        }
+        if self.source[start..self.pos].parse::<f64>().is_err() {
+            return Err(self.error("invalid number"));
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str) -> Result<NodeKind, Box<dyn std::error::Error>> {
+        if !self.source[self.pos..].starts_with(word) {
+            return Err(self.error("expected a JSON value"));
+        }
+        self.pos += word.len();
+        Ok(NodeKind::Scalar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flatten_spans() {
+        let source = "{\n  \"app\": {\n    \"title\": \"Home\",\n    \"tags\": [\"a\", 2]\n  },\n  \"caf\\u00e9\": null\n}\n";
+        let node = parse(source).unwrap();
+        let spans = flatten_spans(&node, ArrayMode::Elements);
+        let keys: Vec<&str> = spans.iter().map(|(key, _)| key.as_str()).collect();
+
+        assert_eq!(keys, ["app.title", "app.tags[0]", "app.tags[1]", "café"]);
+
+        let atomic = flatten_spans(&node, ArrayMode::Atomic);
+        assert_eq!(atomic[1].0, "app.tags");
+        assert_eq!(&source[atomic[1].1.value.clone()], "[\"a\", 2]");
+        assert_eq!(
+            &source[spans[0].1.start..spans[0].1.value.end],
+            "\"title\": \"Home\""
+        );
+        assert_eq!(&source[spans[2].1.value.clone()], "2");
+
+        let lines = LineIndex::new(source);
+        assert_eq!(lines.position(spans[0].1.start), (3, 5));
+        assert_eq!(lines.position(spans[3].1.start), (6, 3));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(parse("{\"a\": }").is_err());
+        assert!(parse("{\"a\": 1,}").is_err());
+        assert!(parse("[1] 2").is_err());
+        assert!(parse("{\"a\": \"\\ud83e\\udd81\"}").is_ok());
+    }
+}
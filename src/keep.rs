@@ -0,0 +1,144 @@
+use crate::translation::split_namespace;
+use regex::Regex;
+
+/// A keep-list entry: an exact key, `prefix.*` for every key below a prefix, or a glob where `*`
+/// matches within one key segment, `**` across segments and `?` a single character
+#[derive(Debug, Clone)]
+pub struct KeepPattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl KeepPattern {
+    pub fn new(pattern: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        if pattern.is_empty() {
+            return Err("Empty keep-list pattern".into());
+        }
+
+        // A trailing `.*` covers every key below the prefix, at any depth
+        let (glob, descendants) = match pattern.strip_suffix(".*") {
+            Some(prefix) => (prefix, r"\..+"),
+            None => (pattern, ""),
+        };
+        let regex = Regex::new(&format!("^{}{}$", glob_to_regex(glob), descendants))
+            .map_err(|e| format!("Invalid keep-list pattern '{}': {}", pattern, e))?;
+
+        Ok(KeepPattern {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether the pattern covers the key; patterns without a namespace match in every namespace
+    pub fn matches(&self, key: &str) -> bool {
+        if self.regex.is_match(key) {
+            return true;
+        }
+        match (split_namespace(&self.pattern).0, split_namespace(key)) {
+            (None, (Some(_), bare_key)) => self.regex.is_match(bare_key),
+            _ => false,
+        }
+    }
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str(r"[^.]*"),
+            '?' => regex.push_str(r"[^.]"),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+/// Keys and patterns that count as used even when no source file mentions them
+#[derive(Debug, Clone, Default)]
+pub struct KeepList {
+    patterns: Vec<KeepPattern>,
+}
+
+impl KeepList {
+    pub fn new(patterns: &[String]) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(KeepList {
+            patterns: patterns
+                .iter()
+                .map(|pattern| KeepPattern::new(pattern))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// First pattern covering the key
+    pub fn matching(&self, key: &str) -> Option<&KeepPattern> {
+        self.patterns.iter().find(|pattern| pattern.matches(key))
+    }
+
+    /// Patterns that match none of the keys, in keep-list order
+    pub fn stale<'a>(&self, keys: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+        let mut matched = vec![false; self.patterns.len()];
+        for key in keys {
+            for (i, pattern) in self.patterns.iter().enumerate() {
+                matched[i] = matched[i] || pattern.matches(key);
+            }
+        }
+
+        self.patterns
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(pattern, _)| pattern.pattern.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, key: &str) -> bool {
+        KeepPattern::new(pattern).unwrap().matches(key)
+    }
+
+    #[test]
+    fn test_keep_patterns() {
+        assert!(matches("errors.*", "errors.network"));
+        assert!(matches("errors.*", "errors.network.timeout"));
+        assert!(!matches("errors.*", "errors"));
+        assert!(!matches("errors.*", "errorsX.network"));
+        assert!(matches("app.title", "app.title"));
+        assert!(!matches("app.title", "app.titles"));
+        assert!(matches("items[0]", "items[0]"));
+
+        assert!(matches("status.*.label", "status.open.label"));
+        assert!(!matches("status.*.label", "status.open.extra.label"));
+        assert!(matches("**.title", "emails.welcome.title"));
+        assert!(matches("email_?", "email_1"));
+
+        assert!(matches("errors.*", "common:errors.network"));
+        assert!(matches("common:errors.*", "common:errors.network"));
+        assert!(!matches("billing:errors.*", "common:errors.network"));
+    }
+
+    #[test]
+    fn test_stale_entries() {
+        let keep = KeepList::new(&["errors.*".to_string(), "legacy.*".to_string()]).unwrap();
+        let keys = vec!["errors.network".to_string(), "app.title".to_string()];
+
+        assert_eq!(
+            keep.matching("errors.network").unwrap().as_str(),
+            "errors.*"
+        );
+        assert!(keep.matching("app.title").is_none());
+        assert_eq!(keep.stale(&keys), ["legacy.*"]);
+    }
+}
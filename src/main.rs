@@ -1,9 +1,26 @@
+mod annotations;
+mod archive;
+mod baseline;
 mod cli;
+mod config;
+mod diff;
+mod extract;
 mod ignore;
+mod interactive;
+mod json_edit;
+mod json_spans;
+mod keep;
 mod output;
+mod plural;
+mod report;
+mod sarif;
+mod scanner;
 mod search;
 mod stats;
+mod template;
 mod translation;
+mod types;
+mod usage;
 
 fn main() {
     if let Err(e) = run() {
@@ -15,28 +32,125 @@ fn main() {
 }
 
 fn run() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = cli::Cli::parse_args();
+    let mut cli = cli::Cli::parse_args();
+    if !cli.no_config {
+        if let Some(config) = config::load_config(cli.config_path.as_deref())? {
+            cli.apply_config(config);
+        }
+    }
+    cli.exit_with_help_if_empty();
     let source_dirs = cli.validate_source_dirs();
 
-    let has_unused = handle_unused(&cli, &source_dirs)?;
+    match &cli.command {
+        Some(cli::Command::Where { key, .. }) => return handle_where(&cli, &source_dirs, key),
+        Some(cli::Command::Restore {
+            archive,
+            keys,
+            create,
+        }) => return handle_restore(archive, keys, *create),
+        None => {}
+    }
+
+    let has_issues = if cli.missing {
+        handle_missing(&cli, &source_dirs)?
+    } else {
+        handle_unused(&cli, &source_dirs)?
+    };
 
-    // In validate mode, exit with error code if unused keys found
-    if cli.validate && has_unused {
+    // In validate mode, exit with error code if unused (or missing) keys found
+    if cli.validate && has_issues {
         std::process::exit(1);
     }
 
     Ok(())
 }
 
+/// Translations of the source locale, where their keys are defined, and the source files to search
+struct Scan {
+    translations: std::collections::HashMap<String, serde_json::Value>,
+    key_locations: std::collections::HashMap<String, translation::KeyLocation>,
+    source_files: Vec<String>,
+    search_options: search::SearchOptions,
+}
+
+fn scan(cli: &cli::Cli, source_dirs: &[String]) -> Result<Scan, Box<dyn std::error::Error>> {
+    let source_path = cli.source_translation_path()?;
+    let (translations, key_locations) =
+        translation::load_located_translations(&source_path, cli.namespaced(), cli.array_mode()?)?;
+    let ignore_patterns = ignore::load_ignore_patterns(&cli.ignore_rules())?;
+    let search_options = cli.search_options()?;
+    let source_files = search::discover_source_files(
+        source_dirs,
+        &ignore_patterns,
+        &search_options.scanners,
+        cli.respect_ignore_files(),
+    )?;
+
+    Ok(Scan {
+        translations,
+        key_locations,
+        source_files,
+        search_options,
+    })
+}
+
+fn handle_missing(
+    cli: &cli::Cli,
+    source_dirs: &[String],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let start_time = std::time::Instant::now();
+
+    let Scan {
+        translations,
+        source_files,
+        search_options,
+        ..
+    } = scan(cli, source_dirs)?;
+    let missing_keys = search::find_missing_keys(&translations, &source_files, &search_options);
+
+    let stats = stats::HuntStats {
+        files_total: source_files.len(),
+        keys_total: translations.len(),
+        unused_keys_count: 0,
+        missing_keys_count: output::distinct_keys(&missing_keys),
+        duration: start_time.elapsed(),
+    };
+
+    match cli.format()? {
+        output::Format::Json => {
+            let report = report::Report::new(report::Mode::Missing, cli, &stats)?
+                .with_missing_keys(&missing_keys);
+            output::print_json_report(&report)?;
+        }
+        output::Format::Sarif => {
+            return Err("SARIF output is only available for unused keys".into());
+        }
+        output::Format::Human if cli.validate => {
+            output::print_validate_missing_results(&missing_keys)
+        }
+        output::Format::Human => {
+            output::print_missing_results(&missing_keys, &stats, cli.show_stats())
+        }
+    }
+
+    Ok(!missing_keys.is_empty())
+}
+
 fn handle_unused(
     cli: &cli::Cli,
     source_dirs: &[String],
 ) -> Result<bool, Box<dyn std::error::Error>> {
     let start_time = std::time::Instant::now();
 
-    let translations = translation::load_translations(&cli.translation_path)?;
-    let source_files = search::discover_source_files(source_dirs)?;
-    let used_keys = search::check_translation_usage(&translations, &source_files);
+    let Scan {
+        translations,
+        key_locations,
+        source_files,
+        search_options,
+    } = scan(cli, source_dirs)?;
+    let usage = search::check_translation_usage(&translations, &source_files, &search_options);
+    let used_keys = usage.used_keys();
+    let stale_keep_entries = search_options.keep.stale(translations.keys());
 
     let unused_keys: Vec<_> = translations
         .keys()
@@ -48,34 +162,295 @@ fn handle_unused(
         files_total: source_files.len(),
         keys_total: translations.len(),
         unused_keys_count: unused_keys.len(),
+        missing_keys_count: 0,
         duration: start_time.elapsed(),
     };
 
-    let has_unused = !unused_keys.is_empty();
+    // With a baseline, only keys it does not accept are reported as unused
+    let comparison = match &cli.baseline {
+        Some(path) => Some(baseline::load_baseline(path)?.compare(&unused_keys, &translations)),
+        None => None,
+    };
+    let reported_keys = comparison
+        .as_ref()
+        .map_or(&unused_keys, |comparison| &comparison.new_keys);
 
+    let has_unused = !reported_keys.is_empty();
+    let format = cli.format()?;
+    if cli.interactive && format != output::Format::Human {
+        return Err("--interactive needs the human output format".into());
+    }
+    if cli.dry_run && format == output::Format::Sarif {
+        return Err("--dry-run needs the human or json output format".into());
+    }
+    if let Some(path) = &cli.write_baseline {
+        baseline::write_baseline(path, &unused_keys)?;
+    }
+
+    let mut removed_keys = unused_keys.clone();
+    let mut keep_list_update = None;
+    if cli.clear_unused && cli.interactive {
+        let locales = locale_translations(cli, &translations)?;
+        let missing_keys = search::find_missing_keys(&translations, &source_files, &search_options);
+        let context = interactive::ReviewContext {
+            locales: &locales,
+            missing_keys: &missing_keys,
+        };
+        let selection = interactive::review(
+            &unused_keys,
+            &context,
+            std::io::stdin().lock(),
+            std::io::stdout(),
+        )?;
+        if !selection.keep_patterns.is_empty() {
+            keep_list_update = Some(config::add_keep_patterns(
+                cli.config.path.as_deref(),
+                &selection.keep_patterns,
+                cli.dry_run,
+            )?);
+        }
+        removed_keys = selection.delete;
+    }
+
+    let mut planned_changes = Vec::new();
+    let mut kept_array_elements: Vec<String> = Vec::new();
+    let mut archived = None;
     if cli.clear_unused {
-        translation::remove_unused_keys(&cli.translation_path, &unused_keys, &used_keys)?;
+        // Every locale loses the same keys so they stay in sync with the source locale
+        for target in cli.clear_targets()? {
+            planned_changes.extend(translation::plan_unused_key_removal(
+                &target,
+                &removed_keys,
+                &used_keys,
+                cli.cleanup_options(),
+            )?);
+        }
+        // Unused array elements before one used by its index stay in the files
+        let cut: std::collections::HashSet<&str> = planned_changes
+            .iter()
+            .flat_map(|change| change.removed.iter().map(|removed| removed.key.as_str()))
+            .collect();
+        removed_keys.retain(|key| cut.contains(key.as_str()));
+        kept_array_elements = planned_changes
+            .iter()
+            .flat_map(|change| change.kept.iter().cloned())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if cli.dry_run {
+            if let Some(path) = &cli.patch {
+                std::fs::write(path, diff::patch(&planned_changes))?;
+            }
+        } else {
+            // Archived first so nothing is lost if writing the archive fails
+            if let Some(dir) = cli.archive_dir() {
+                let archive = archive::archive_changes(&planned_changes);
+                if !archive.keys.is_empty() {
+                    archived = Some((archive::write_archive(dir, &archive)?, archive.keys.len()));
+                }
+            }
+            translation::apply_changes(&planned_changes)?;
+        }
+    }
+
+    match format {
+        output::Format::Json => {
+            let mut report = report::Report::new(report::Mode::Unused, cli, &stats)?
+                .with_unused_keys(&unused_keys, &key_locations)
+                .with_stale_keep_entries(&stale_keep_entries);
+            if cli.clear_unused {
+                report = report.with_cleanup(&removed_keys, &kept_array_elements);
+            }
+            if cli.dry_run {
+                report = report.with_dry_run(&planned_changes, cli.patch.as_deref());
+            }
+            if let (Some(path), Some(comparison)) = (&cli.baseline, &comparison) {
+                report = report.with_baseline(path, comparison);
+            }
+            if cli.with_locations {
+                report = report.with_usage(&usage, None);
+            }
+            output::print_json_report(&report)?;
+            return Ok(has_unused);
+        }
+        output::Format::Sarif => {
+            let log = sarif::sarif_log(
+                &unused_keys,
+                &key_locations,
+                &cli.source_translation_path()?,
+                comparison.as_ref(),
+            );
+            output::print_sarif_log(&log)?;
+            return Ok(has_unused);
+        }
+        output::Format::Human => {}
+    }
+
+    if cli.with_locations {
+        output::print_used_locations(&usage);
+    }
+
+    if cli.dry_run {
+        match &cli.patch {
+            Some(path) => output::print_patch_written(path, planned_changes.len()),
+            None => {
+                for change in &planned_changes {
+                    output::print_diff(&diff::unified_diff(change));
+                }
+            }
+        }
+        if cli.interactive {
+            output::print_interactive_results(
+                &removed_keys,
+                unused_keys.len() - removed_keys.len(),
+                true,
+            );
+        } else {
+            output::print_dry_run_results(
+                &removed_keys,
+                &key_locations,
+                planned_changes.len(),
+                cli.show_keys(),
+            );
+        }
+    } else if cli.interactive {
+        output::print_interactive_results(
+            &removed_keys,
+            unused_keys.len() - removed_keys.len(),
+            false,
+        );
+    } else if cli.clear_unused {
         output::print_cleared_results(
-            &unused_keys,
+            &removed_keys,
+            &key_locations,
             &stats,
-            cli.show_stats,
-            cli.show_keys,
+            cli.show_stats(),
+            cli.show_keys(),
             cli.clear_unused,
         );
     } else {
         // In validate mode, show minimal output
         if cli.validate {
-            output::print_validate_results(&unused_keys, &stats);
+            output::print_validate_results(reported_keys, &stats);
         } else {
             output::print_results(
-                &unused_keys,
+                reported_keys,
+                &key_locations,
                 &stats,
-                cli.show_stats,
-                cli.show_keys,
+                cli.show_stats(),
+                cli.show_keys(),
                 cli.clear_unused,
             );
         }
+        if let (Some(path), Some(comparison)) = (&cli.baseline, &comparison) {
+            output::print_baseline_summary(path, comparison);
+        }
+    }
+
+    output::print_kept_array_elements(&kept_array_elements, &key_locations);
+    output::print_stale_keep_entries(&stale_keep_entries);
+    if let Some((path, keys_count)) = &archived {
+        output::print_archive_written(path, *keys_count);
+    }
+    if let Some((path, added_count)) = &keep_list_update {
+        output::print_keep_list_updated(path, *added_count, cli.dry_run);
+    }
+    if let Some(path) = &cli.write_baseline {
+        output::print_baseline_written(path, unused_keys.len());
     }
 
     Ok(has_unused)
 }
+
+fn handle_restore(
+    archive_path: &str,
+    patterns: &[String],
+    create: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let archive = archive::load_archive(archive_path)?;
+    let summary = archive::plan_restore(&archive, patterns, create)?;
+    translation::apply_changes(&summary.changes)?;
+    output::print_restore_results(&summary);
+    Ok(())
+}
+
+/// Translations of every locale for showing values side by side; just the source translations
+/// when there is no locales root
+fn locale_translations(
+    cli: &cli::Cli,
+    translations: &std::collections::HashMap<String, serde_json::Value>,
+) -> Result<Vec<interactive::LocaleTranslations>, Box<dyn std::error::Error>> {
+    let translation_path = cli.translation_path()?;
+    if cli.source_locale.is_none() {
+        return Ok(vec![(translation_path.to_string(), translations.clone())]);
+    }
+
+    translation::find_locales(translation_path)?
+        .into_iter()
+        .map(|(locale, path)| {
+            let translations = translation::load_translations(
+                &path.to_string_lossy(),
+                cli.namespaced(),
+                cli.array_mode()?,
+            )?;
+            Ok((locale, translations))
+        })
+        .collect()
+}
+
+fn handle_where(
+    cli: &cli::Cli,
+    source_dirs: &[String],
+    key: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let start_time = std::time::Instant::now();
+
+    let Scan {
+        translations,
+        key_locations,
+        source_files,
+        search_options,
+    } = scan(cli, source_dirs)?;
+
+    // Unqualified keys live in the default namespace
+    let key = match &search_options.default_namespace {
+        Some(default_namespace) if translation::split_namespace(key).0.is_none() => format!(
+            "{}{}{}",
+            default_namespace,
+            translation::NAMESPACE_SEPARATOR,
+            key
+        ),
+        _ => key.to_string(),
+    };
+    if !translations.contains_key(&key) {
+        return Err(format!("Key '{}' does not exist in the translations", key).into());
+    }
+
+    let usage = search::check_translation_usage(&translations, &source_files, &search_options);
+
+    if cli.format()? == output::Format::Sarif {
+        return Err("SARIF output is only available for unused keys".into());
+    }
+    if cli.format()? == output::Format::Json {
+        let stats = stats::HuntStats {
+            files_total: source_files.len(),
+            keys_total: translations.len(),
+            unused_keys_count: 0,
+            missing_keys_count: 0,
+            duration: start_time.elapsed(),
+        };
+        let report = report::Report::new(report::Mode::Where, cli, &stats)?
+            .with_usage(&usage, Some(std::slice::from_ref(&key)));
+        output::print_json_report(&report)?;
+    } else {
+        output::print_where_results(
+            &key,
+            key_locations.get(&key),
+            &usage.locations(&key),
+            usage.kept_by(&key),
+            usage.referenced_by(&key),
+        );
+    }
+
+    Ok(())
+}
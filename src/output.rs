@@ -1,9 +1,36 @@
+use crate::search::MissingKey;
 use crate::stats::HuntStats;
+use crate::translation::KeyLocation;
+use crate::usage::{UsageIndex, UsageLocation};
 use colored::*;
+use std::collections::HashMap;
+
+/// How results are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Colored text for people
+    Human,
+    /// Versioned JSON report for scripts and dashboards
+    Json,
+    /// SARIF 2.1.0 log for code scanning tools
+    Sarif,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "human" | "text" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            _ => Err(format!("Unknown format '{}' (expected human, json or sarif)", name).into()),
+        }
+    }
+}
 
 /// Print results with optional statistics and keys list
 pub fn print_results(
     unused_keys: &[String],
+    key_locations: &HashMap<String, KeyLocation>,
     stats: &HuntStats,
     show_stats: bool,
     show_keys: bool,
@@ -25,7 +52,7 @@ pub fn print_results(
 
     // Show keys list first if flag is set (but not when clearing unused keys)
     if show_keys && !is_clear_unused_flag {
-        print_unused_keys(unused_keys);
+        print_unused_keys(unused_keys, key_locations);
         // Add spacing between keys and stats if both are shown
         if show_stats {
             println!();
@@ -38,15 +65,13 @@ pub fn print_results(
     }
 }
 
-/// Print unused keys list
-pub fn print_unused_keys(unused_keys: &[String]) {
+/// Print unused keys list, each with where it is defined
+pub fn print_unused_keys(unused_keys: &[String], key_locations: &HashMap<String, KeyLocation>) {
     if unused_keys.is_empty() {
         return;
     }
 
-    for key in unused_keys {
-        println!("- {key}");
-    }
+    print_key_list(unused_keys, key_locations);
 
     println!(
         "\n{} {} unused translation keys\n",
@@ -55,8 +80,36 @@ pub fn print_unused_keys(unused_keys: &[String]) {
     );
 }
 
+/// Print keys one per line, each with where it is defined
+fn print_key_list(keys: &[String], key_locations: &HashMap<String, KeyLocation>) {
+    for key in keys {
+        match key_locations.get(key) {
+            Some(location) => println!(
+                "- {} {}",
+                key,
+                format!("{}:{}:{}", location.file, location.line, location.column).dimmed()
+            ),
+            None => println!("- {key}"),
+        }
+    }
+}
+
 /// Print statistics about the hunt
 fn print_stats(stats: &HuntStats, is_clear_unused_flag: bool) {
+    print_scan_stats(stats);
+
+    if !is_clear_unused_flag {
+        println!(
+            "{} {} {}",
+            "Keys not used:".cyan(),
+            stats.unused_keys_count.to_string().red().bold(),
+            "".dimmed()
+        );
+    }
+}
+
+/// Print the statistics shared by every mode
+fn print_scan_stats(stats: &HuntStats) {
     println!(
         "{} {} {}",
         "Files scanned:".cyan(),
@@ -75,15 +128,6 @@ fn print_stats(stats: &HuntStats, is_clear_unused_flag: bool) {
         stats.formatted_duration().bold(),
         "".dimmed()
     );
-
-    if !is_clear_unused_flag {
-        println!(
-            "{} {} {}",
-            "Keys not used:".cyan(),
-            stats.unused_keys_count.to_string().red().bold(),
-            "".dimmed()
-        );
-    }
 }
 
 /// Print error messages with consistent styling
@@ -94,6 +138,7 @@ pub fn print_error(message: &str) {
 /// Print cleared results message
 pub fn print_cleared_results(
     unused_keys: &[String],
+    key_locations: &HashMap<String, KeyLocation>,
     stats: &HuntStats,
     show_stats: bool,
     show_keys: bool,
@@ -124,12 +169,10 @@ pub fn print_cleared_results(
         );
     }
 
-    // Show keys list first if flag is set (but not when clearing unused keys)
-    if show_keys && !is_clear_unused_flag {
+    // Show the removed keys with where they were defined
+    if show_keys && !unused_keys.is_empty() {
         println!("\nRemoved keys:");
-        for key in unused_keys {
-            println!("- {key}");
-        }
+        print_key_list(unused_keys, key_locations);
         // Add spacing between keys and stats if both are shown
         if show_stats {
             println!();
@@ -143,6 +186,151 @@ pub fn print_cleared_results(
     }
 }
 
+/// Print a unified diff with added lines in green and removed lines in red
+pub fn print_diff(diff: &str) {
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            println!("{}", line.bold());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Print what --clear would do, after the diff; with `show_keys`, the keys and where they are
+/// defined
+pub fn print_dry_run_results(
+    unused_keys: &[String],
+    key_locations: &HashMap<String, KeyLocation>,
+    files_count: usize,
+    show_keys: bool,
+) {
+    if unused_keys.is_empty() {
+        println!("{}", "✓ No unused translation keys found!".green());
+    } else {
+        if show_keys {
+            println!("\nKeys that would be removed:");
+            print_key_list(unused_keys, key_locations);
+        }
+        println!(
+            "\n{} {} unused translation keys would be removed from {} files (dry run, nothing written)",
+            "ℹ".cyan(),
+            unused_keys.len().to_string().bold(),
+            files_count.to_string().bold()
+        );
+    }
+}
+
+/// Print the outcome of an interactive cleanup
+pub fn print_interactive_results(removed_keys: &[String], kept_count: usize, dry_run: bool) {
+    println!(
+        "\n{} {} unused translation keys {}, {} kept",
+        "✓".green(),
+        removed_keys.len().to_string().green().bold(),
+        if dry_run {
+            "would be removed"
+        } else {
+            "removed"
+        },
+        kept_count.to_string().bold()
+    );
+}
+
+/// Confirm that patterns were added to the keep-list of a config file
+pub fn print_keep_list_updated(file: &std::path::Path, added_count: usize, dry_run: bool) {
+    if dry_run {
+        println!(
+            "{} {} patterns would be added to the keep list in {} (dry run, nothing written)",
+            "ℹ".cyan(),
+            added_count.to_string().bold(),
+            file.display()
+        );
+    } else {
+        println!(
+            "{} Added {} patterns to the keep list in {}",
+            "✓".green(),
+            added_count.to_string().bold(),
+            file.display()
+        );
+    }
+}
+
+/// Warn about keep-list entries that match no translation key
+pub fn print_stale_keep_entries(stale: &[String]) {
+    if stale.is_empty() {
+        return;
+    }
+    println!(
+        "\n{} {} keep-list entries match no translation key and can be removed:",
+        "⚠️".yellow(),
+        stale.len().to_string().yellow().bold()
+    );
+    for pattern in stale {
+        println!("- {}", pattern);
+    }
+}
+
+/// Print the unused array elements --clear left in place so that a later element keeps its index
+pub fn print_kept_array_elements(kept: &[String], key_locations: &HashMap<String, KeyLocation>) {
+    if kept.is_empty() {
+        return;
+    }
+    println!(
+        "\n{} {} unused array elements were kept because a later element is used by its index:",
+        "ℹ".cyan(),
+        kept.len().to_string().bold()
+    );
+    print_key_list(kept, key_locations);
+}
+
+/// Confirm that removed keys were archived
+pub fn print_archive_written(file: &std::path::Path, keys_count: usize) {
+    println!(
+        "{} Archived {} removed keys to {} (undo with `hunt restore {}`)",
+        "✓".green(),
+        keys_count.to_string().bold(),
+        file.display(),
+        file.display()
+    );
+}
+
+/// Print what `hunt restore` put back
+pub fn print_restore_results(summary: &crate::archive::RestoreSummary) {
+    if summary.restored.is_empty() {
+        println!("{}", "✓ Nothing to restore".green());
+    } else {
+        println!(
+            "{} Restored {} keys in {} files",
+            "✓".green(),
+            summary.restored.len().to_string().green().bold(),
+            summary.changes.len().to_string().bold()
+        );
+    }
+    if !summary.already_present.is_empty() {
+        println!(
+            "{} {} keys were already present and left untouched",
+            "ℹ".cyan(),
+            summary.already_present.len().to_string().bold()
+        );
+    }
+}
+
+/// Confirm that a patch file was written
+pub fn print_patch_written(file: &str, files_count: usize) {
+    println!(
+        "{} Wrote changes to {} files to patch {}",
+        "✓".green(),
+        files_count.to_string().bold(),
+        file
+    );
+}
+
 /// Print validation results (minimal output for pre-commit hooks)
 pub fn print_validate_results(unused_keys: &[String], _stats: &HuntStats) {
     if unused_keys.is_empty() {
@@ -155,3 +343,186 @@ pub fn print_validate_results(unused_keys: &[String], _stats: &HuntStats) {
         );
     }
 }
+
+/// Print keys used in code but missing from the translations, with every location
+pub fn print_missing_results(missing_keys: &[MissingKey], stats: &HuntStats, show_stats: bool) {
+    if missing_keys.is_empty() {
+        println!("{}", "✓ No missing translation keys found!".green());
+    } else {
+        for missing in missing_keys {
+            println!(
+                "- {} {}",
+                missing.key,
+                format!("{}:{}:{}", missing.file, missing.line, missing.column).dimmed()
+            );
+        }
+
+        println!(
+            "\n{} {} missing translation keys",
+            "⚠️".yellow(),
+            distinct_keys(missing_keys).to_string().red().bold()
+        );
+    }
+
+    if show_stats {
+        println!();
+        print_scan_stats(stats);
+        println!(
+            "{} {} {}",
+            "Keys missing:".cyan(),
+            stats.missing_keys_count.to_string().red().bold(),
+            "".dimmed()
+        );
+    }
+}
+
+/// Print missing-key validation results (minimal output for pre-commit hooks)
+pub fn print_validate_missing_results(missing_keys: &[MissingKey]) {
+    if missing_keys.is_empty() {
+        println!("{}", "✓ No missing translation keys found!".green());
+    } else {
+        for missing in missing_keys {
+            println!(
+                "{}:{}:{} {}",
+                missing.file, missing.line, missing.column, missing.key
+            );
+        }
+        println!(
+            "{} {} missing translation keys found",
+            "✗".red(),
+            distinct_keys(missing_keys).to_string().red().bold()
+        );
+    }
+}
+
+/// Number of distinct keys among missing key locations
+pub fn distinct_keys(missing_keys: &[MissingKey]) -> usize {
+    missing_keys
+        .iter()
+        .map(|missing| missing.key.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len()
+}
+
+/// Print the locations a key is used at
+fn print_locations(locations: &[&UsageLocation]) {
+    for location in locations {
+        println!(
+            "  {} {}",
+            format!("{}:{}:{}", location.file, location.line, location.column).dimmed(),
+            format!("({})", location.kind.as_str()).dimmed()
+        );
+    }
+}
+
+/// Print every used key with the locations it is used at
+pub fn print_used_locations(usage: &UsageIndex) {
+    let mut used_keys: Vec<String> = usage.used_keys().into_iter().collect();
+    used_keys.sort();
+
+    for key in &used_keys {
+        println!("{} {}", "✓".green(), key);
+        print_locations(&usage.locations(key));
+    }
+
+    println!(
+        "\n{} {} used translation keys\n",
+        "✓".green(),
+        used_keys.len().to_string().green().bold()
+    );
+}
+
+/// Print where a single key is defined and used
+pub fn print_where_results(
+    key: &str,
+    definition: Option<&KeyLocation>,
+    locations: &[&UsageLocation],
+    kept_by: Option<&str>,
+    referenced_by: Option<&str>,
+) {
+    if let Some(definition) = definition {
+        println!(
+            "{} {}",
+            "Defined at:".cyan(),
+            format!(
+                "{}:{}:{}",
+                definition.file, definition.line, definition.column
+            )
+            .dimmed()
+        );
+    }
+
+    if locations.is_empty() {
+        if let Some(pattern) = kept_by {
+            println!(
+                "{} {} is not used but is on the keep-list ({})",
+                "✓".green(),
+                key.bold(),
+                pattern
+            );
+        } else if let Some(from) = referenced_by {
+            println!(
+                "{} {} is not used in code but is referenced by the value of {}",
+                "✓".green(),
+                key.bold(),
+                from.bold()
+            );
+        } else {
+            println!("{} {} is not used anywhere", "⚠️".yellow(), key.bold());
+        }
+        return;
+    }
+
+    println!(
+        "{} {} is used in {} places",
+        "✓".green(),
+        key.bold(),
+        locations.len().to_string().green().bold()
+    );
+    print_locations(locations);
+}
+
+/// Print a machine-readable report
+pub fn print_json_report(report: &crate::report::Report) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", report.to_json()?);
+    Ok(())
+}
+
+/// Print a SARIF log
+pub fn print_sarif_log(log: &serde_json::Value) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(log)?);
+    Ok(())
+}
+
+/// Print how the unused keys compare with the baseline
+pub fn print_baseline_summary(file: &str, comparison: &crate::baseline::BaselineComparison) {
+    println!(
+        "{} {} unused translation keys accepted by baseline {}",
+        "ℹ".cyan(),
+        comparison.baselined_keys.len().to_string().bold(),
+        file.dimmed()
+    );
+
+    if comparison.is_stale() {
+        println!(
+            "\n{} Baseline entries that can be removed (run with --write-baseline to refresh):",
+            "⚠️".yellow()
+        );
+        for key in &comparison.now_used {
+            println!("- {} {}", key, "(now used)".dimmed());
+        }
+        for key in &comparison.deleted {
+            println!("- {} {}", key, "(deleted)".dimmed());
+        }
+    }
+}
+
+/// Confirm that a baseline file was written
+pub fn print_baseline_written(file: &str, keys_count: usize) {
+    println!(
+        "{} Wrote {} unused translation keys to baseline {}",
+        "✓".green(),
+        keys_count.to_string().bold(),
+        file
+    );
+}
@@ -0,0 +1,144 @@
+//! i18next plural and context variants of a key, such as `items_one` or `friend_male`, which code
+//! uses through their base key: `t('items', { count })`, `t('friend', { context: 'male' })`.
+
+use std::collections::HashMap;
+
+/// CLDR plural categories, the suffixes of the i18next v4 JSON format
+const PLURAL_CATEGORIES: &[&str] = &["zero", "one", "two", "few", "many", "other"];
+
+/// Separator between a key and its plural or context suffix
+const SUFFIX_SEPARATOR: char = '_';
+
+/// Context entry matching any suffix
+const ANY_CONTEXT: &str = "*";
+
+/// Plural suffixes of an i18next JSON format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluralFormat {
+    /// JSON v4: CLDR categories (`items_one`, `items_other`, `place_ordinal_two`)
+    V4,
+    /// JSON v3: `items_plural` and numbered forms (`items_0`, `items_1`, ...)
+    V3,
+    /// No plural suffixes
+    None,
+}
+
+impl PluralFormat {
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "v4" => Ok(PluralFormat::V4),
+            "v3" => Ok(PluralFormat::V3),
+            "none" => Ok(PluralFormat::None),
+            other => Err(format!(
+                "Unknown plural format '{}' (expected 'v4', 'v3' or 'none')",
+                other
+            )
+            .into()),
+        }
+    }
+}
+
+/// Recognizes plural and context variants of keys
+#[derive(Debug, Clone)]
+pub struct Variants {
+    plurals: PluralFormat,
+    /// Context values (`male`, `female`), or `*` for any
+    contexts: Vec<String>,
+}
+
+impl Variants {
+    pub fn new(plurals: PluralFormat, contexts: &[String]) -> Self {
+        Variants {
+            plurals,
+            contexts: contexts.to_vec(),
+        }
+    }
+
+    /// Base key of a plural and/or context variant: `items_one` -> `items`,
+    /// `friend_male_other` -> `friend` (with the `male` context)
+    pub fn base_of<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let without_plural = self.strip_plural(key);
+        let base = self
+            .strip_context(without_plural.unwrap_or(key))
+            .or(without_plural)?;
+
+        // The base must keep a last segment of its own
+        let last_segment = base.rsplit(['.', ':']).next().unwrap_or(base);
+        (!last_segment.is_empty()).then_some(base)
+    }
+
+    /// Variants of the given keys grouped by base key
+    pub fn groups<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) -> HashMap<String, Vec<String>> {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for key in keys {
+            if let Some(base) = self.base_of(key) {
+                groups
+                    .entry(base.to_string())
+                    .or_default()
+                    .push(key.clone());
+            }
+        }
+        groups
+    }
+
+    fn strip_plural<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let (rest, suffix) = key.rsplit_once(SUFFIX_SEPARATOR)?;
+        match self.plurals {
+            PluralFormat::V4 if PLURAL_CATEGORIES.contains(&suffix) => {
+                Some(rest.strip_suffix("_ordinal").unwrap_or(rest))
+            }
+            PluralFormat::V3
+                if suffix == "plural"
+                    || (!suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit())) =>
+            {
+                Some(rest)
+            }
+            _ => None,
+        }
+    }
+
+    fn strip_context<'a>(&self, key: &'a str) -> Option<&'a str> {
+        let (rest, suffix) = key.rsplit_once(SUFFIX_SEPARATOR)?;
+        let in_last_segment = !suffix.is_empty() && !suffix.contains(['.', ':', '[']);
+        let known = self
+            .contexts
+            .iter()
+            .any(|context| context == suffix || context == ANY_CONTEXT);
+        (in_last_segment && known).then_some(rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_of() {
+        let v4 = Variants::new(
+            PluralFormat::V4,
+            &["male".to_string(), "female".to_string()],
+        );
+        assert_eq!(v4.base_of("items_one"), Some("items"));
+        assert_eq!(v4.base_of("cart.items_other"), Some("cart.items"));
+        assert_eq!(v4.base_of("place_ordinal_few"), Some("place"));
+        assert_eq!(v4.base_of("friend_male"), Some("friend"));
+        assert_eq!(v4.base_of("friend_female_one"), Some("friend"));
+        assert_eq!(v4.base_of("common:items_zero"), Some("common:items"));
+        assert_eq!(v4.base_of("items_plural"), None);
+        assert_eq!(v4.base_of("error_message"), None);
+        assert_eq!(v4.base_of("_one"), None);
+        assert_eq!(v4.base_of("list._other"), None);
+
+        let v3 = Variants::new(PluralFormat::V3, &["*".to_string()]);
+        assert_eq!(v3.base_of("items_plural"), Some("items"));
+        assert_eq!(v3.base_of("items_2"), Some("items"));
+        assert_eq!(v3.base_of("items_one"), Some("items"));
+        assert_eq!(v3.base_of("items_one.title"), None);
+
+        let none = Variants::new(PluralFormat::None, &[]);
+        assert_eq!(none.base_of("items_one"), None);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::baseline::BaselineComparison;
+use crate::cli::Cli;
+use crate::diff;
+use crate::search::MissingKey;
+use crate::stats::HuntStats;
+use crate::translation::{split_namespace, FileChange, KeyLocation};
+use crate::usage::UsageIndex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Version of the JSON report schema, bumped on every incompatible change
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Machine-readable report printed by `--format json`
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub schema_version: u32,
+    pub tool: Tool,
+    pub mode: Mode,
+    pub translation_path: String,
+    pub source_locale: Option<String>,
+    pub namespaced: bool,
+    pub validate: bool,
+    /// Whether unused keys were removed from the translation files (false with `--dry-run`)
+    pub cleared: bool,
+    pub stats: Stats,
+    pub unused_keys: Vec<UnusedKey>,
+    /// Keys `--clear` removed, or would remove with `--dry-run`; `null` without `--clear`
+    pub removed_keys: Option<Vec<String>>,
+    /// Unused array elements `--clear` kept so that a later element keeps its index
+    pub kept_array_elements: Vec<String>,
+    /// Changes `--dry-run` would make; `null` without `--dry-run`
+    pub dry_run: Option<DryRunReport>,
+    pub missing_keys: Vec<MissingKeyEntry>,
+    /// Used keys with their locations; `null` unless locations were collected
+    pub used_keys: Option<Vec<UsedKey>>,
+    /// Comparison with `--baseline`; `null` without a baseline
+    pub baseline: Option<BaselineReport>,
+    /// Keep-list entries that match no translation key
+    pub stale_keep_entries: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+/// What the run looked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Unused,
+    Missing,
+    Where,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub files_total: usize,
+    pub keys_total: usize,
+    pub unused_keys_count: usize,
+    pub missing_keys_count: usize,
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnusedKey {
+    pub key: String,
+    pub namespace: Option<String>,
+    /// Translation file the key is defined in, and the position of its property name
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Accepted by the baseline, so it does not fail validation
+    pub baselined: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BaselineReport {
+    pub file: String,
+    pub new_keys_count: usize,
+    pub baselined_keys_count: usize,
+    /// Baseline entries that are no longer unused
+    pub now_used: Vec<String>,
+    /// Baseline entries that no longer exist in the translations
+    pub deleted: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DryRunReport {
+    /// File the `--patch` was written to; `null` without `--patch`
+    pub patch: Option<String>,
+    pub changes: Vec<PlannedChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlannedChange {
+    pub file: String,
+    pub removed_keys: Vec<String>,
+    /// Unified diff of the change
+    pub diff: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MissingKeyEntry {
+    pub key: String,
+    pub namespace: Option<String>,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsedKey {
+    pub key: String,
+    pub locations: Vec<Location>,
+    /// Keep-list entry covering the key; `null` when it is not on the keep-list
+    pub kept_by: Option<String>,
+    /// Used key whose value references the key (`$t(key)`, `@:key`); `null` otherwise
+    pub referenced_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Location {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    /// "exact", "dynamic" or "annotation"
+    pub kind: &'static str,
+}
+
+impl Report {
+    /// Empty report for a run with the given options
+    pub fn new(
+        mode: Mode,
+        cli: &Cli,
+        stats: &HuntStats,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Report {
+            schema_version: SCHEMA_VERSION,
+            tool: Tool {
+                name: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION"),
+            },
+            mode,
+            translation_path: cli.translation_path()?.to_string(),
+            source_locale: cli.source_locale.clone(),
+            namespaced: cli.namespaced(),
+            validate: cli.validate,
+            cleared: cli.clear_unused && !cli.dry_run && mode == Mode::Unused,
+            stats: Stats {
+                files_total: stats.files_total,
+                keys_total: stats.keys_total,
+                unused_keys_count: stats.unused_keys_count,
+                missing_keys_count: stats.missing_keys_count,
+                duration_ms: stats.duration.as_millis(),
+            },
+            unused_keys: Vec::new(),
+            removed_keys: None,
+            kept_array_elements: Vec::new(),
+            dry_run: None,
+            missing_keys: Vec::new(),
+            used_keys: None,
+            baseline: None,
+            stale_keep_entries: Vec::new(),
+        })
+    }
+
+    /// Add unused keys, sorted, with where each one is defined
+    pub fn with_unused_keys(
+        mut self,
+        unused_keys: &[String],
+        key_locations: &HashMap<String, KeyLocation>,
+    ) -> Self {
+        let mut unused_keys = unused_keys.to_vec();
+        unused_keys.sort();
+
+        self.unused_keys = unused_keys
+            .into_iter()
+            .map(|key| {
+                let location = key_locations.get(&key);
+                UnusedKey {
+                    namespace: self.namespace_of(&key),
+                    file: location.map(|location| location.file.clone()),
+                    line: location.map(|location| location.line),
+                    column: location.map(|location| location.column),
+                    baselined: false,
+                    key,
+                }
+            })
+            .collect();
+        self
+    }
+
+    /// Add what `--clear` cut out of the translation files and what it had to leave, sorted
+    pub fn with_cleanup(mut self, removed_keys: &[String], kept_array_elements: &[String]) -> Self {
+        let mut removed_keys = removed_keys.to_vec();
+        removed_keys.sort();
+        self.removed_keys = Some(removed_keys);
+        self.kept_array_elements = kept_array_elements.to_vec();
+        self
+    }
+
+    /// Add the changes a dry run planned and the patch file they were written to
+    pub fn with_dry_run(mut self, changes: &[FileChange], patch: Option<&str>) -> Self {
+        self.dry_run = Some(DryRunReport {
+            patch: patch.map(|path| path.to_string()),
+            changes: changes
+                .iter()
+                .map(|change| PlannedChange {
+                    file: change.path.display().to_string(),
+                    removed_keys: change
+                        .removed
+                        .iter()
+                        .map(|removed| removed.key.clone())
+                        .collect(),
+                    diff: diff::unified_diff(change),
+                })
+                .collect(),
+        });
+        self
+    }
+
+    /// Add the comparison with a baseline, marking the unused keys it accepts
+    pub fn with_baseline(mut self, file: &str, comparison: &BaselineComparison) -> Self {
+        for unused in &mut self.unused_keys {
+            unused.baselined = comparison.baselined_keys.binary_search(&unused.key).is_ok();
+        }
+        self.baseline = Some(BaselineReport {
+            file: file.to_string(),
+            new_keys_count: comparison.new_keys.len(),
+            baselined_keys_count: comparison.baselined_keys.len(),
+            now_used: comparison.now_used.clone(),
+            deleted: comparison.deleted.clone(),
+        });
+        self
+    }
+
+    /// Add missing keys in the order they were found
+    pub fn with_missing_keys(mut self, missing_keys: &[MissingKey]) -> Self {
+        self.missing_keys = missing_keys
+            .iter()
+            .map(|missing| MissingKeyEntry {
+                key: missing.key.clone(),
+                namespace: self.namespace_of(&missing.key),
+                file: missing.file.clone(),
+                line: missing.line,
+                column: missing.column,
+            })
+            .collect();
+        self
+    }
+
+    /// Add the locations of the given keys (every used key when `keys` is None)
+    pub fn with_usage(mut self, usage: &UsageIndex, keys: Option<&[String]>) -> Self {
+        let mut keys: Vec<String> = match keys {
+            Some(keys) => keys.to_vec(),
+            None => usage.used_keys().into_iter().collect(),
+        };
+        keys.sort();
+
+        self.used_keys = Some(
+            keys.into_iter()
+                .map(|key| UsedKey {
+                    locations: usage
+                        .locations(&key)
+                        .into_iter()
+                        .map(|location| Location {
+                            file: location.file.clone(),
+                            line: location.line,
+                            column: location.column,
+                            kind: location.kind.as_str(),
+                        })
+                        .collect(),
+                    kept_by: usage.kept_by(&key).map(|pattern| pattern.to_string()),
+                    referenced_by: usage.referenced_by(&key).map(|from| from.to_string()),
+                    key,
+                })
+                .collect(),
+        );
+        self
+    }
+
+    /// Add keep-list entries that match no translation key
+    pub fn with_stale_keep_entries(mut self, stale: &[String]) -> Self {
+        self.stale_keep_entries = stale.to_vec();
+        self
+    }
+
+    fn namespace_of(&self, key: &str) -> Option<String> {
+        if !self.namespaced {
+            return None;
+        }
+        split_namespace(key)
+            .0
+            .map(|namespace| namespace.to_string())
+    }
+
+    /// Serialize the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::time::Duration;
+
+    #[test]
+    fn test_unused_report_schema() {
+        let cli = Cli::parse_from(["hunt", "locales/en", "--namespaces"]);
+        let stats = HuntStats {
+            files_total: 3,
+            keys_total: 4,
+            unused_keys_count: 2,
+            missing_keys_count: 0,
+            duration: Duration::from_millis(12),
+        };
+        let key_locations = HashMap::from([(
+            "common:app.old".to_string(),
+            KeyLocation {
+                file: "locales/en/common.json".to_string(),
+                line: 3,
+                column: 5,
+            },
+        )]);
+        let unused = vec!["common:b".to_string(), "common:app.old".to_string()];
+
+        let report = Report::new(Mode::Unused, &cli, &stats)
+            .unwrap()
+            .with_unused_keys(&unused, &key_locations);
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["mode"], "unused");
+        assert_eq!(json["stats"]["duration_ms"], 12);
+        assert_eq!(json["unused_keys"][0]["key"], "common:app.old");
+        assert_eq!(json["unused_keys"][0]["namespace"], "common");
+        assert_eq!(json["unused_keys"][0]["file"], "locales/en/common.json");
+        assert_eq!(json["unused_keys"][0]["line"], 3);
+        assert!(json["unused_keys"][1]["file"].is_null());
+        assert!(json["used_keys"].is_null());
+        assert!(json["baseline"].is_null());
+        assert_eq!(json["unused_keys"][0]["baselined"], false);
+        assert_eq!(json["missing_keys"].as_array().unwrap().len(), 0);
+        assert!(json["removed_keys"].is_null());
+        assert!(json["dry_run"].is_null());
+    }
+
+    #[test]
+    fn test_dry_run_report_is_not_cleared() {
+        let cli = Cli::parse_from(["hunt", "en.json", "--clear", "--dry-run"]);
+        let stats = HuntStats {
+            files_total: 1,
+            keys_total: 3,
+            unused_keys_count: 2,
+            missing_keys_count: 0,
+            duration: Duration::from_millis(1),
+        };
+        let removed = vec!["steps[2]".to_string()];
+        let kept = vec!["steps[0]".to_string()];
+        let change = FileChange {
+            path: "en.json".into(),
+            original: "{\n  \"a\": \"A\",\n  \"b\": \"B\"\n}\n".to_string(),
+            updated: "{\n  \"a\": \"A\"\n}\n".to_string(),
+            removed: vec![crate::translation::RemovedKey {
+                key: "b".to_string(),
+                segments: Vec::new(),
+                value: serde_json::json!("B"),
+            }],
+            kept: Vec::new(),
+        };
+
+        let report = Report::new(Mode::Unused, &cli, &stats)
+            .unwrap()
+            .with_cleanup(&removed, &kept)
+            .with_dry_run(&[change], Some("hunt.patch"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["cleared"], false);
+        assert_eq!(json["removed_keys"], serde_json::json!(["steps[2]"]));
+        assert_eq!(json["kept_array_elements"], serde_json::json!(["steps[0]"]));
+        assert_eq!(json["dry_run"]["patch"], "hunt.patch");
+        assert_eq!(json["dry_run"]["changes"][0]["file"], "en.json");
+        assert_eq!(
+            json["dry_run"]["changes"][0]["removed_keys"],
+            serde_json::json!(["b"])
+        );
+        assert!(json["dry_run"]["changes"][0]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("-  \"b\": \"B\""));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::baseline::BaselineComparison;
+use crate::translation::KeyLocation;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/JeevesInc/hunt";
+
+const UNUSED_RULE: &str = "unused-translation-key";
+
+/// Base of relative artifact URIs: the root of the scanned repository
+const SOURCE_ROOT: &str = "%SRCROOT%";
+
+/// Build a SARIF 2.1.0 log with one result per unused key, at its position in the translation file
+/// or at the start of `translation_path` when the position is not known. With a baseline, results
+/// carry their `baselineState`
+pub fn sarif_log(
+    unused_keys: &[String],
+    key_locations: &HashMap<String, KeyLocation>,
+    translation_path: &str,
+    baseline: Option<&BaselineComparison>,
+) -> Value {
+    let mut unused_keys = unused_keys.to_vec();
+    unused_keys.sort();
+
+    let mut results = Vec::new();
+
+    for key in &unused_keys {
+        let mut result = json!({
+            "ruleId": UNUSED_RULE,
+            "ruleIndex": 0,
+            "level": "warning",
+            "message": { "text": format!("Translation key '{}' is not used", key) },
+            "partialFingerprints": { "translationKey/v1": key },
+        });
+
+        if let Some(baseline) = baseline {
+            let accepted = baseline.baselined_keys.binary_search(key).is_ok();
+            result["baselineState"] = json!(if accepted { "unchanged" } else { "new" });
+        }
+
+        // Code scanning rejects results without a location
+        result["locations"] = json!([match key_locations.get(key) {
+            Some(key_location) => location(
+                &key_location.file,
+                key_location.line,
+                Some(key_location.column)
+            ),
+            None => location(translation_path, 1, None),
+        }]);
+
+        results.push(result);
+    }
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": [
+                        {
+                            "id": UNUSED_RULE,
+                            "name": "UnusedTranslationKey",
+                            "shortDescription": { "text": "Unused translation key" },
+                            "fullDescription": { "text": "The translation key is not used anywhere in the scanned source files and can be removed." },
+                            "defaultConfiguration": { "level": "warning" },
+                        },
+                    ],
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// SARIF physical location. Relative paths become URIs with forward slashes relative to the
+/// source root, absolute paths `file://` URIs
+fn location(file: &str, line: usize, column: Option<usize>) -> Value {
+    let uri = file.replace('\\', "/");
+    let artifact_location = if std::path::Path::new(file).is_absolute() {
+        // `C:/...` on Windows needs a slash after the scheme's `//`
+        let separator = if uri.starts_with('/') { "" } else { "/" };
+        json!({ "uri": format!("file://{}{}", separator, uri) })
+    } else {
+        json!({ "uri": uri.trim_start_matches("./"), "uriBaseId": SOURCE_ROOT })
+    };
+
+    let mut region = json!({ "startLine": line });
+    if let Some(column) = column {
+        region["startColumn"] = json!(column);
+    }
+
+    json!({
+        "physicalLocation": {
+            "artifactLocation": artifact_location,
+            "region": region,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unused_key_results() {
+        let key_locations = HashMap::from([(
+            "common:app.old".to_string(),
+            KeyLocation {
+                file: "./locales/en/common.json".to_string(),
+                line: 3,
+                column: 5,
+            },
+        )]);
+        let log = sarif_log(
+            &["common:app.old".to_string(), "common:app.gone".to_string()],
+            &key_locations,
+            "locales/en",
+            None,
+        );
+
+        assert_eq!(log["version"], "2.1.0");
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results[1]["ruleId"], UNUSED_RULE);
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "locales/en/common.json"
+        );
+        assert_eq!(location["artifactLocation"]["uriBaseId"], SOURCE_ROOT);
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 5);
+
+        // Without a known position the result points at the translations
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "locales/en");
+        assert_eq!(location["region"]["startLine"], 1);
+    }
+}
@@ -0,0 +1,269 @@
+use crate::extract::{self, SourceKind};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Extensions scanned when no others are configured
+const DEFAULT_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx"];
+
+/// Scanner used for well-known extensions when the user adds them without naming one
+const KNOWN_SCANNERS: &[(&str, Scanner)] = &[
+    ("js", Scanner::Script),
+    ("jsx", Scanner::Script),
+    ("ts", Scanner::Script),
+    ("tsx", Scanner::Script),
+    ("mjs", Scanner::Script),
+    ("cjs", Scanner::Script),
+    ("mts", Scanner::Script),
+    ("cts", Scanner::Script),
+    ("vue", Scanner::Markup),
+    ("svelte", Scanner::Markup),
+    ("html", Scanner::Markup),
+    ("astro", Scanner::Markup),
+    ("mdx", Scanner::Mdx),
+];
+
+/// How the content of a source file is prepared before searching for keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scanner {
+    /// Search the whole file as plain text
+    Text,
+    /// JavaScript/TypeScript: comments are not searched
+    Script,
+    /// HTML-like templates (Vue, Svelte, Astro): markup and script comments are not searched
+    Markup,
+    /// MDX: `{/* */}`, `<!-- -->` and script comments are not searched
+    Mdx,
+}
+
+impl Scanner {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Scanner::Text),
+            "script" | "js" => Some(Scanner::Script),
+            "markup" | "html" => Some(Scanner::Markup),
+            "mdx" => Some(Scanner::Mdx),
+            _ => None,
+        }
+    }
+
+    /// Prepare the content of the file at `path` for searching. Skipped regions are blanked out so
+    /// line numbers stay intact
+    pub fn prepare<'a>(&self, path: &Path, content: &'a str) -> Cow<'a, str> {
+        match self {
+            Scanner::Text => Cow::Borrowed(content),
+            Scanner::Script | Scanner::Mdx => {
+                Cow::Owned(blank_comments(content, self.source_kind(path)))
+            }
+            Scanner::Markup => Cow::Owned(blank_markup_comments(content)),
+        }
+    }
+
+    /// How the file at `path` is tokenized
+    pub fn source_kind(&self, path: &Path) -> SourceKind {
+        match self {
+            Scanner::Markup => SourceKind::Markup,
+            Scanner::Mdx => SourceKind::Mdx,
+            _ => SourceKind::for_path(path, false),
+        }
+    }
+}
+
+/// Mapping from file extension (without the dot) to the scanner used for it
+#[derive(Debug, Clone)]
+pub struct ScannerMap {
+    scanners: HashMap<String, Scanner>,
+}
+
+impl ScannerMap {
+    /// Scanner for a file, or None when its extension is not scanned
+    pub fn scanner_for(&self, path: &Path) -> Option<Scanner> {
+        let ext = path.extension()?.to_str()?;
+        self.scanners.get(ext).copied()
+    }
+}
+
+/// Build the extension map from the defaults plus `ext` or `ext=scanner` specs
+pub fn build_scanner_map(specs: &[String]) -> Result<ScannerMap, Box<dyn std::error::Error>> {
+    let mut scanners: HashMap<String, Scanner> = DEFAULT_EXTENSIONS
+        .iter()
+        .map(|ext| (ext.to_string(), known_scanner(ext)))
+        .collect();
+
+    for spec in specs {
+        let (ext, scanner) = match spec.split_once('=') {
+            Some((ext, name)) => {
+                let scanner = Scanner::from_name(name.trim()).ok_or_else(|| {
+                    format!(
+                        "Unknown scanner '{}' for extension '{}' (expected text, script, markup or mdx)",
+                        name, ext
+                    )
+                })?;
+                (ext, scanner)
+            }
+            None => (
+                spec.as_str(),
+                known_scanner(spec.trim().trim_start_matches('.')),
+            ),
+        };
+
+        let ext = ext.trim().trim_start_matches('.');
+        if ext.is_empty() {
+            return Err(format!("Invalid extension '{}'", spec).into());
+        }
+        scanners.insert(ext.to_string(), scanner);
+    }
+
+    Ok(ScannerMap { scanners })
+}
+
+fn known_scanner(ext: &str) -> Scanner {
+    KNOWN_SCANNERS
+        .iter()
+        .find(|(known, _)| *known == ext)
+        .map(|(_, scanner)| *scanner)
+        .unwrap_or(Scanner::Text)
+}
+
+/// Replace a skipped region with spaces, keeping newlines and byte offsets
+fn blank(out: &mut String, region: &str) {
+    for c in region.chars() {
+        if c == '\n' {
+            out.push('\n');
+        } else {
+            out.extend(std::iter::repeat_n(' ', c.len_utf8()));
+        }
+    }
+}
+
+/// Blank out comments, found with the tokenizer so that strings, regex literals and JSX text
+/// containing `//` (`/https?:\/\//`, `<p>https://example.com</p>`) are left untouched
+fn blank_comments(content: &str, kind: SourceKind) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut copied = 0;
+
+    for (start, end) in extract::comment_ranges(content, kind) {
+        if start < copied {
+            continue;
+        }
+        out.push_str(&content[copied..start]);
+        blank(&mut out, &content[start..end]);
+        copied = end;
+    }
+
+    out.push_str(&content[copied..]);
+    out
+}
+
+/// Blank out `<!-- -->` comments, and script comments inside `<script>` blocks
+fn blank_markup_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+
+    loop {
+        let comment = rest.find("<!--");
+        let script = find_script_block(rest);
+
+        match (comment, script) {
+            (Some(start), script) if script.is_none_or(|(s, _)| start < s) => {
+                let end = rest[start..]
+                    .find("-->")
+                    .map_or(rest.len(), |p| start + p + 3);
+                out.push_str(&rest[..start]);
+                blank(&mut out, &rest[start..end]);
+                rest = &rest[end..];
+            }
+            (_, Some((start, end))) => {
+                out.push_str(&rest[..start]);
+                out.push_str(&blank_comments(&rest[start..end], SourceKind::Jsx));
+                rest = &rest[end..];
+            }
+            _ => {
+                out.push_str(rest);
+                return out;
+            }
+        }
+    }
+}
+
+/// Byte range of the body of the first `<script ...>...</script>` block
+fn find_script_block(content: &str) -> Option<(usize, usize)> {
+    let open = content.find("<script")?;
+    let body_start = open + content[open..].find('>')? + 1;
+    let body_end = content[body_start..]
+        .find("</script")
+        .map_or(content.len(), |p| body_start + p);
+    Some((body_start, body_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_scanner_map() {
+        let specs = vec!["vue".to_string(), ".mdx=text".to_string(), "md".to_string()];
+        assert_eq!(
+            build_scanner_map(&["mdx".to_string()])
+                .unwrap()
+                .scanner_for(Path::new("a.mdx")),
+            Some(Scanner::Mdx)
+        );
+        let map = build_scanner_map(&specs).unwrap();
+
+        assert_eq!(map.scanner_for(Path::new("a.tsx")), Some(Scanner::Script));
+        assert_eq!(map.scanner_for(Path::new("a.vue")), Some(Scanner::Markup));
+        assert_eq!(map.scanner_for(Path::new("a.mdx")), Some(Scanner::Text));
+        assert_eq!(map.scanner_for(Path::new("a.md")), Some(Scanner::Text));
+        assert_eq!(map.scanner_for(Path::new("a.css")), None);
+        assert!(build_scanner_map(&["vue=pug".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_script_comments_are_blanked() {
+        let code = "t('a.b'); // t('c.d')\n/* t('e.f') */ const s = \"// t('g.h')\";";
+        let prepared = Scanner::Script.prepare(Path::new("a.tsx"), code);
+
+        assert!(prepared.contains("t('a.b')"));
+        assert!(!prepared.contains("c.d"));
+        assert!(!prepared.contains("e.f"));
+        assert!(prepared.contains("g.h"));
+        assert_eq!(prepared.len(), code.len());
+        assert_eq!(prepared.lines().count(), code.lines().count());
+    }
+
+    #[test]
+    fn test_double_slashes_outside_comments_are_kept() {
+        let jsx = "<p>Visit https://example.com {t('app.title')}</p>";
+        let prepared = Scanner::Script.prepare(Path::new("a.tsx"), jsx);
+        assert!(prepared.contains("t('app.title')"));
+
+        let regex = "if (/https?:\\/\\//.test(u) && t('status.open')) {} // t('old.key')";
+        let prepared = Scanner::Script.prepare(Path::new("a.ts"), regex);
+        assert!(prepared.contains("t('status.open')"));
+        assert!(!prepared.contains("old.key"));
+    }
+
+    #[test]
+    fn test_markup_comments_are_blanked() {
+        let code = "<!-- {{ $t('old.key') }} -->\n<p>{{ $t('new.key') }}</p>\n<script>\n// t('x.y')\nt('z.w')\n</script>";
+        let prepared = Scanner::Markup.prepare(Path::new("a.vue"), code);
+
+        assert!(!prepared.contains("old.key"));
+        assert!(prepared.contains("new.key"));
+        assert!(!prepared.contains("x.y"));
+        assert!(prepared.contains("z.w"));
+    }
+
+    #[test]
+    fn test_mdx_comments_are_blanked() {
+        let code = "import { a } from './a' // t('import.key')\n\n# Title\n\n{/* t('old.key') */}\n<!-- t('older.key') -->\nSee https://example.com {t('new.key')}\n";
+        let prepared = Scanner::Mdx.prepare(Path::new("a.mdx"), code);
+
+        assert!(!prepared.contains("import.key"));
+        assert!(!prepared.contains("old.key"));
+        assert!(!prepared.contains("older.key"));
+        assert!(prepared.contains("https://example.com {t('new.key')}"));
+        assert_eq!(prepared.len(), code.len());
+    }
+}
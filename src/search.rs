@@ -1,88 +1,288 @@
+use crate::annotations;
+use crate::extract::{self, CallSites, Extraction, KeyUsage};
+use crate::keep::{KeepList, KeepPattern};
+use crate::plural::Variants;
+use crate::scanner::{Scanner, ScannerMap};
+use crate::template::{KeyTemplate, TemplateFinder};
+use crate::translation::{self, split_namespace, NAMESPACE_SEPARATOR};
+use crate::types::TypeIndex;
+use crate::usage::{MatchKind, UsageIndex, UsageLocation};
+use ignore::WalkBuilder;
 use indicatif::{ProgressBar, ProgressStyle};
 use regex::Regex;
 use serde_json::Value;
-use std::collections::HashSet;
+use std::borrow::Cow;
 use std::fs;
-use walkdir::WalkDir;
 
-/// Supported file extensions
-const SUPPORTED_EXTENSIONS: &[&str] = &["js", "jsx", "ts", "tsx"];
+/// Hunt-specific ignore file honored during discovery, in addition to `.gitignore` and `.ignore`
+const CUSTOM_IGNORE_FILENAME: &str = ".huntignore";
 
-/// Discover source files in the given directories, skipping ignored directories during traversal
-pub fn discover_source_files(source_dirs: &[String]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let ignore_patterns = crate::ignore::load_ignore_patterns();
+/// Discover source files in the given directories, skipping ignored directories during traversal.
+/// Unless disabled, nested `.gitignore`, `.ignore` and `.huntignore` files are honored as well.
+pub fn discover_source_files(
+    source_dirs: &[String],
+    ignore_patterns: &crate::ignore::IgnorePatterns,
+    scanners: &ScannerMap,
+    respect_ignore_files: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let mut all_files = Vec::new();
-    
+
     for source_dir in source_dirs {
-        let walker = WalkDir::new(source_dir)
+        let patterns = ignore_patterns.clone();
+        let mut builder = WalkBuilder::new(source_dir);
+        builder
             .follow_links(false)
-            .into_iter()
-            .filter_entry(|e| {
+            .hidden(false)
+            .parents(respect_ignore_files)
+            .ignore(respect_ignore_files)
+            .git_ignore(respect_ignore_files)
+            .git_global(respect_ignore_files)
+            .git_exclude(respect_ignore_files)
+            .require_git(false)
+            .filter_entry(move |e| {
                 // For directories, check if we should skip them entirely
-                if e.file_type().is_dir() {
-                    let path_str = e.path().to_string_lossy();
+                if e.file_type().is_some_and(|t| t.is_dir()) {
                     // If path should be ignored, return false to skip traversing into it
-                    !ignore_patterns.should_ignore(&path_str)
+                    !patterns.should_skip_dir(e.path())
                 } else {
                     // For files, always include them (we'll filter later)
                     true
                 }
             });
-        
-        for entry in walker {
+        if respect_ignore_files {
+            builder.add_custom_ignore_filename(CUSTOM_IGNORE_FILENAME);
+        }
+
+        for entry in builder.build() {
             let entry = match entry {
                 Ok(e) => e,
                 Err(_) => continue, // Skip files we can't read
             };
-            
+
             // Only process files, not directories
-            if !entry.file_type().is_file() {
+            if !entry.file_type().is_some_and(|t| t.is_file()) {
                 continue;
             }
-            
+
             let path = entry.path();
-            
-            // Check if file has supported extension
-            if let Some(ext) = path.extension() {
-                if let Some(ext_str) = ext.to_str() {
-                    if SUPPORTED_EXTENSIONS.contains(&ext_str) {
-                        let file_path = path.to_string_lossy().to_string();
-                        // Final check: make sure the file path itself isn't ignored (for glob patterns like *.log)
-                        if !ignore_patterns.should_ignore(&file_path) {
-                            all_files.push(file_path);
-                        }
-                    }
+
+            // Check if file has a scanned extension
+            if scanners.scanner_for(path).is_some() {
+                // Final check: make sure the file path itself isn't ignored (for glob patterns like *.log)
+                if !ignore_patterns.should_ignore(path, false) {
+                    all_files.push(path.to_string_lossy().to_string());
                 }
             }
         }
     }
-    
+
     Ok(all_files)
 }
 
-/// Check which translation keys are used in source files
+/// Options controlling how source files are searched for key usages
+pub struct SearchOptions {
+    pub scanners: ScannerMap,
+    pub extraction: Extraction,
+    pub call_sites: CallSites,
+    /// Namespace for keys without an explicit one; `Some` when translation keys are namespaced
+    pub default_namespace: Option<String>,
+    /// Record every usage of a key instead of stopping at the first one
+    pub collect_locations: bool,
+    /// Keys treated as used without appearing in any source file
+    pub keep: KeepList,
+    /// Plural and context variants used through their base key
+    pub variants: Variants,
+}
+
+impl SearchOptions {
+    /// Translation key referenced by an extracted key, given the namespace bound at the call site
+    fn resolve_key(&self, key: &str, bound_namespace: Option<&str>) -> String {
+        let (explicit_namespace, bare_key) = split_namespace(key);
+        match &self.default_namespace {
+            Some(default_namespace) => {
+                let namespace = explicit_namespace
+                    .or(bound_namespace)
+                    .unwrap_or(default_namespace);
+                format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, bare_key)
+            }
+            None => bare_key.to_string(),
+        }
+    }
+
+    /// Template of the translation keys a dynamically built key can produce; without namespaces,
+    /// a namespace in the template is ignored like in literal keys
+    fn resolve_template(
+        &self,
+        template: &KeyTemplate,
+        bound_namespace: Option<&str>,
+    ) -> KeyTemplate {
+        match &self.default_namespace {
+            Some(_) if split_namespace(template.as_str()).0.is_some() => template.clone(),
+            Some(default_namespace) => {
+                template.with_namespace(Some(bound_namespace.unwrap_or(default_namespace)))
+            }
+            None => template.with_namespace(None),
+        }
+    }
+
+    /// Pattern matching a translation key in plain text: keys are written with their namespace
+    /// (`billing:invoice.title`), or bare when they belong to the default namespace
+    fn key_pattern(&self, key: &str) -> String {
+        match (&self.default_namespace, split_namespace(key)) {
+            (Some(default_namespace), (Some(namespace), bare_key))
+                if namespace == default_namespace =>
+            {
+                format!("{}|{}", whole_key_pattern(key), whole_key_pattern(bare_key))
+            }
+            _ => whole_key_pattern(key),
+        }
+    }
+}
+
+/// Check which translation keys are used in source files, and where
 pub fn check_translation_usage(
-    translations: &std::collections::HashMap<String, Value>, 
-    source_files: &[String]
-) -> HashSet<String> {
+    translations: &std::collections::HashMap<String, Value>,
+    source_files: &[String],
+    options: &SearchOptions,
+) -> UsageIndex {
     let pb = create_progress_bar();
     pb.set_message("The lion is on the hunt…");
     pb.enable_steady_tick(std::time::Duration::from_millis(50));
-    
-    let compiled_patterns = compile_regex_patterns(translations);
-    let base_prefixes = extract_base_prefixes(translations);
-    let dynamic_patterns = compile_dynamic_patterns(&base_prefixes);
-    
-    // Check both exact matches and dynamic patterns in a single pass through files
-    let used_keys = find_used_keys_combined(
-        &compiled_patterns, 
-        &dynamic_patterns, 
-        &base_prefixes,
-        source_files
-    );
-    
+
+    // With AST extraction, plain-text files are still matched with regexes
+    let (extracted_files, regex_files): (Vec<String>, Vec<String>) =
+        source_files.iter().cloned().partition(|file_path| {
+            options.extraction == Extraction::Ast
+                && options
+                    .scanners
+                    .scanner_for(std::path::Path::new(file_path))
+                    != Some(Scanner::Text)
+        });
+
+    // Base keys of plural and context variants, and whole arrays, are searched like keys of their own
+    let mut groups = options.variants.groups(translations.keys());
+    for (array, elements) in array_groups(translations.keys()) {
+        groups.entry(array).or_default().extend(elements);
+    }
+    let searched = with_base_keys(translations, &groups);
+
+    // Types may be declared in any file, so they are collected before keys are matched
+    let types = collect_types(source_files, options);
+    let mut template_keys = TemplateKeys::new(&searched, &types);
+    let mut usage =
+        find_used_keys_extracted(&searched, &mut template_keys, &extracted_files, options);
+
+    if !regex_files.is_empty() {
+        let compiled_patterns = compile_regex_patterns(&searched, options);
+
+        // Check both exact matches and key templates in a single pass through files
+        usage.merge(find_used_keys_combined(
+            &searched,
+            &compiled_patterns,
+            &mut template_keys,
+            &regex_files,
+            options,
+        ));
+    }
+
+    // A used base key uses all of its variants, and a used array all of its elements
+    for (base, variants) in &groups {
+        let locations = if translations.contains_key(base) {
+            usage.locations(base).into_iter().cloned().collect()
+        } else {
+            usage.remove(base)
+        };
+        for variant in variants {
+            for location in &locations {
+                usage.record(variant, location.clone());
+            }
+        }
+    }
+
+    // Keep-list entries count as used even when no source file mentions them
+    for key in translations.keys() {
+        if let Some(pattern) = options.keep.matching(key) {
+            usage.keep(key, pattern.as_str());
+        }
+    }
+
+    propagate_references(translations, &groups, options, &mut usage);
+
     pb.finish_and_clear();
-    used_keys
+    usage
+}
+
+/// Mark keys referenced from the values of used keys (`$t(key)`, `@:key`) as used, transitively
+fn propagate_references(
+    translations: &std::collections::HashMap<String, Value>,
+    groups: &std::collections::HashMap<String, Vec<String>>,
+    options: &SearchOptions,
+    usage: &mut UsageIndex,
+) {
+    let references =
+        translation::find_references(translations, options.default_namespace.is_some());
+    if references.is_empty() {
+        return;
+    }
+
+    let mut pending: Vec<String> = usage.used_keys().into_iter().collect();
+    while let Some(key) = pending.pop() {
+        let Some(targets) = references.get(&key) else {
+            continue;
+        };
+        for target in targets {
+            // A reference to a base key or array uses its variants or elements, like a call would
+            let resolved = translations
+                .get_key_value(target)
+                .map(|(target, _)| target)
+                .into_iter()
+                .chain(groups.get(target).into_iter().flatten());
+            for resolved in resolved {
+                if !usage.contains(resolved) {
+                    usage.reference(resolved, &key);
+                    pending.push(resolved.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Keys of array elements by the key of every array they are in: `steps` -> `steps[0]`,
+/// `steps[1].title`. Code reading a whole array (`t('steps', { returnObjects: true })`) uses them all
+fn array_groups<'a>(
+    keys: impl IntoIterator<Item = &'a String>,
+) -> std::collections::HashMap<String, Vec<String>> {
+    let mut groups: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+    for key in keys {
+        for (bracket, _) in key.match_indices('[').filter(|(bracket, _)| *bracket > 0) {
+            groups
+                .entry(key[..bracket].to_string())
+                .or_default()
+                .push(key.clone());
+        }
+    }
+    groups
+}
+
+/// Translations plus the base keys of variants and the arrays that have no entry of their own
+fn with_base_keys<'a>(
+    translations: &'a std::collections::HashMap<String, Value>,
+    groups: &std::collections::HashMap<String, Vec<String>>,
+) -> Cow<'a, std::collections::HashMap<String, Value>> {
+    let missing: Vec<&String> = groups
+        .keys()
+        .filter(|base| !translations.contains_key(*base))
+        .collect();
+    if missing.is_empty() {
+        return Cow::Borrowed(translations);
+    }
+
+    let mut searched = translations.clone();
+    for base in missing {
+        searched.insert(base.clone(), Value::Null);
+    }
+    Cow::Owned(searched)
 }
 
 /// Create a progress bar with consistent styling
@@ -91,19 +291,19 @@ fn create_progress_bar() -> ProgressBar {
     pb.set_style(
         ProgressStyle::default_spinner()
             .template("{spinner:.blue} {msg}")
-            .unwrap()
+            .unwrap(),
     );
     pb
 }
 
 /// Compile regex patterns for all translation keys
 fn compile_regex_patterns(
-    translations: &std::collections::HashMap<String, Value>
+    translations: &std::collections::HashMap<String, Value>,
+    options: &SearchOptions,
 ) -> Vec<(String, Regex)> {
     let mut compiled_patterns: Vec<(String, Regex)> = Vec::new();
     for (key, _value) in translations.iter() {
-        let escaped_key = regex::escape(key);
-        let pattern = format!(r"\b{}\b", escaped_key);
+        let pattern = options.key_pattern(key);
         if let Ok(re) = Regex::new(&pattern) {
             compiled_patterns.push((key.clone(), re));
         }
@@ -111,231 +311,646 @@ fn compile_regex_patterns(
     compiled_patterns
 }
 
-/// Extract base prefixes from translation keys (e.g., "expenseCategory" from "expenseCategory.foo")
-/// Returns a map of prefix -> set of keys that start with that prefix
-fn extract_base_prefixes(
-    translations: &std::collections::HashMap<String, Value>
-) -> std::collections::HashMap<String, std::collections::HashSet<String>> {
-    let mut prefix_map: std::collections::HashMap<String, std::collections::HashSet<String>> = 
-        std::collections::HashMap::new();
-    
-    for key in translations.keys() {
-        // Find the base prefix - everything before the last dot, or the key itself if no dot
-        if let Some(dot_pos) = key.rfind('.') {
-            let prefix = key[..dot_pos].to_string();
-            prefix_map
-                .entry(prefix)
-                .or_insert_with(std::collections::HashSet::new)
-                .insert(key.clone());
+/// Pattern matching a key as a whole word; word boundaries are only required next to word
+/// characters, so array element keys like `steps[0]` still match before a quote
+fn whole_key_pattern(key: &str) -> String {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if key.starts_with(is_word) { r"\b" } else { "" };
+    let end = if key.ends_with(is_word) { r"\b" } else { "" };
+    format!("{}{}{}", start, regex::escape(key), end)
+}
+
+/// Translation keys produced by key templates, narrowed by the declared types and computed once
+/// per distinct template
+struct TemplateKeys<'a> {
+    translations: &'a std::collections::HashMap<String, Value>,
+    types: &'a TypeIndex,
+    matches: std::collections::HashMap<String, Vec<String>>,
+}
+
+impl<'a> TemplateKeys<'a> {
+    fn new(
+        translations: &'a std::collections::HashMap<String, Value>,
+        types: &'a TypeIndex,
+    ) -> Self {
+        TemplateKeys {
+            translations,
+            types,
+            matches: std::collections::HashMap::new(),
         }
     }
-    
-    prefix_map
+
+    fn matching(&mut self, template: &KeyTemplate) -> &[String] {
+        let translations = self.translations;
+        let template = template.narrow(|expression| self.types.values_of(expression));
+        self.matches
+            .entry(template.regex_source())
+            .or_insert_with(|| {
+                let regex = template.regex();
+                translations
+                    .keys()
+                    .filter(|key| regex.is_match(key))
+                    .cloned()
+                    .collect()
+            })
+    }
 }
 
-/// Compile regex patterns for dynamic key usage (e.g., "expenseCategory.${...}")
-fn compile_dynamic_patterns(
-    prefixes: &std::collections::HashMap<String, std::collections::HashSet<String>>
-) -> Vec<(String, Regex)> {
-    let mut patterns: Vec<(String, Regex)> = Vec::new();
-    
-    for (prefix, _keys) in prefixes.iter() {
-        let escaped_prefix = regex::escape(prefix);
-        
-        // Pattern to match dynamic key construction:
-        // - Template literals: `expenseCategory.${var}` or `Reimbursement:expenseCategory.${var}`
-        // - String literals: "expenseCategory.${var}" or 'expenseCategory.${var}'
-        // - Function calls: t(`expenseCategory.${var}`, ...) or t("expenseCategory.${var}", ...)
-        // - With namespace: "Reimbursement:expenseCategory.${var}"
-        // 
-        // Pattern matches:
-        // - prefix.${...}
-        // - Namespace:prefix.${...}
-        // - 'prefix.${...}' or "prefix.${...}" or `prefix.${...}`
-        //
-        // Uses a flexible pattern that matches prefix followed by .${ and some content
-        
-        // Match: prefix.${variable} or Namespace:prefix.${variable}
-        // This covers template literals, string literals, and function arguments
-        let pattern = format!(
-            r#"{}\.\$\{{[^}}]+\}}"#,
-            escaped_prefix
-        );
-        
-        if let Ok(re) = Regex::new(&pattern) {
-            patterns.push((prefix.clone(), re));
+/// Collect the string-literal unions, enums and `as const` arrays declared in the source files
+fn collect_types(source_files: &[String], options: &SearchOptions) -> TypeIndex {
+    let mut types = TypeIndex::new();
+    for file_path in source_files {
+        let scanner = options
+            .scanners
+            .scanner_for(std::path::Path::new(file_path));
+        let Some(scanner) = scanner.filter(|scanner| *scanner != Scanner::Text) else {
+            continue;
+        };
+        if let Ok(content) = fs::read_to_string(file_path) {
+            types.scan(&scanner.prepare(std::path::Path::new(file_path), &content));
         }
     }
-    
-    patterns
+    types
 }
 
-/// Find used keys by scanning source files (checks both exact matches and dynamic patterns in one pass)
+/// Find used keys by scanning source files (checks both exact matches and key templates in one pass).
+/// Unless all locations are collected, only the first usage of each key is recorded
 fn find_used_keys_combined(
-    exact_patterns: &[(String, Regex)], 
-    dynamic_patterns: &[(String, Regex)],
-    base_prefixes: &std::collections::HashMap<String, std::collections::HashSet<String>>,
-    source_files: &[String]
-) -> HashSet<String> {
-    let mut used_keys = HashSet::new();
-    let mut found_prefixes = HashSet::new();
-    // Cache prefixes where all keys have been found via exact matches (avoid recalculating)
-    let mut prefixes_complete = HashSet::new();
-    
-    // Build reverse lookup: key -> prefix (for optimization)
-    let mut key_to_prefix: std::collections::HashMap<String, String> = std::collections::HashMap::new();
-    for (prefix, keys) in base_prefixes {
-        for key in keys {
-            key_to_prefix.insert(key.clone(), prefix.clone());
-        }
-    }
-    
-    // Single pass through all files - check both exact and dynamic patterns
+    translations: &std::collections::HashMap<String, Value>,
+    exact_patterns: &[(String, Regex)],
+    template_keys: &mut TemplateKeys,
+    source_files: &[String],
+    options: &SearchOptions,
+) -> UsageIndex {
+    let collect_all = options.collect_locations;
+    let mut usage = UsageIndex::default();
+    let finder = TemplateFinder::new();
+
+    // Single pass through all files - check both exact matches and key templates
     for file_path in source_files {
-        if let Ok(content) = fs::read_to_string(file_path) {
+        if let Ok(raw_content) = fs::read_to_string(file_path) {
+            let scanner = options
+                .scanners
+                .scanner_for(std::path::Path::new(file_path))
+                .unwrap_or(Scanner::Text);
+            let content = scanner.prepare(std::path::Path::new(file_path), &raw_content);
+            let lines = extract::LineIndex::new(&content);
+            let location = |offset: usize, kind: MatchKind| {
+                let (line, column) = lines.position(offset);
+                UsageLocation {
+                    file: file_path.clone(),
+                    line,
+                    column,
+                    kind,
+                }
+            };
+
+            // Annotations live in comments, which the scanner strips
+            let kind = (scanner != Scanner::Text)
+                .then(|| scanner.source_kind(std::path::Path::new(file_path)));
+            record_annotations(&raw_content, kind, file_path, translations, &mut usage);
+
             // STEP 1: Check for exact key matches FIRST
-            // (Skip keys whose prefix was already found dynamically - we'll mark them all anyway)
             for (key, pattern) in exact_patterns {
-                // Skip if key already found or its prefix was found dynamically
-                if used_keys.contains(key) {
+                // Skip if key already found
+                if !collect_all && usage.contains(key) {
                     continue;
                 }
-                
-                // Optimization: Skip exact check if prefix was already found dynamically
-                // (all keys with that prefix will be marked as used later)
-                if let Some(prefix) = key_to_prefix.get(key) {
-                    if found_prefixes.contains(prefix) {
-                        continue;
+
+                // `steps[1]` uses one element, not the whole `steps` array
+                let matches = pattern
+                    .find_iter(&content)
+                    .filter(|m| !content[m.end()..].starts_with('['));
+                for m in matches {
+                    usage.record(key, location(m.start(), MatchKind::Exact));
+                    if !collect_all {
+                        break;
                     }
                 }
-                
-                if pattern.is_match(&content) {
-                    used_keys.insert(key.clone());
-                    
-                    // Check if this was the last key for this prefix
-                    if let Some(prefix) = key_to_prefix.get(key) {
-                        if !prefixes_complete.contains(prefix) {
-                            if let Some(keys_with_prefix) = base_prefixes.get(prefix) {
-                                if keys_with_prefix.iter().all(|k| used_keys.contains(k)) {
-                                    prefixes_complete.insert(prefix.clone());
-                                }
-                            }
-                        }
+            }
+
+            // STEP 2: Mark the keys produced by dynamically built keys (`errors.${code}.title`)
+            for (offset, template) in finder.find(&content) {
+                let template = options.resolve_template(&template, None);
+                for key in template_keys.matching(&template) {
+                    if collect_all || !usage.contains(key) {
+                        usage.record(key, location(offset, MatchKind::Dynamic));
                     }
                 }
             }
-            
-            // STEP 2: Check dynamic patterns for prefixes that aren't complete yet
-            for (prefix, pattern) in dynamic_patterns {
-                // Skip if already found dynamically
-                if found_prefixes.contains(prefix) {
-                    continue;
-                }
-                
-                // Skip if all keys with this prefix are already found via exact matches
-                if prefixes_complete.contains(prefix) {
-                    continue;
-                }
-                
-                // Check if all keys are now found (check once per file instead of per-pattern)
-                let all_keys_found = if let Some(keys_with_prefix) = base_prefixes.get(prefix) {
-                    keys_with_prefix.iter().all(|key| used_keys.contains(key))
-                } else {
-                    false
-                };
-                
-                if all_keys_found {
-                    prefixes_complete.insert(prefix.clone());
-                    continue;
+        }
+    }
+
+    usage
+}
+
+/// Find used keys by parsing source files and collecting keys passed to translation call sites
+fn find_used_keys_extracted(
+    translations: &std::collections::HashMap<String, Value>,
+    template_keys: &mut TemplateKeys,
+    source_files: &[String],
+    options: &SearchOptions,
+) -> UsageIndex {
+    let mut usage_index = UsageIndex::default();
+
+    for file_path in source_files {
+        let path = std::path::Path::new(file_path);
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let kind = options
+            .scanners
+            .scanner_for(path)
+            .unwrap_or(Scanner::Script)
+            .source_kind(path);
+
+        record_annotations(
+            &content,
+            Some(kind),
+            file_path,
+            translations,
+            &mut usage_index,
+        );
+
+        for usage in extract::extract_usages(&content, kind, &options.call_sites) {
+            let namespace = usage.namespace.as_deref();
+            let location = |kind: MatchKind| UsageLocation {
+                file: file_path.clone(),
+                line: usage.line,
+                column: usage.column,
+                kind,
+            };
+            match &usage.key {
+                KeyUsage::Literal(key) => {
+                    // Keys that legitimately contain the namespace separator are taken as-is
+                    let key = if translations.contains_key(key) {
+                        key.clone()
+                    } else {
+                        options.resolve_key(key, namespace)
+                    };
+                    if translations.contains_key(&key) {
+                        usage_index.record(&key, location(MatchKind::Exact));
+                    }
                 }
-                
-                // Only run regex if we still need to check
-                if pattern.is_match(&content) {
-                    found_prefixes.insert(prefix.clone());
+                KeyUsage::Dynamic(template) => {
+                    let template = options.resolve_template(template, namespace);
+                    for key in template_keys.matching(&template) {
+                        usage_index.record(key, location(MatchKind::Dynamic));
+                    }
                 }
             }
         }
     }
-    
-    // Mark all keys with dynamically found prefixes as used
-    for prefix in found_prefixes {
-        if let Some(keys_with_prefix) = base_prefixes.get(&prefix) {
-            for key in keys_with_prefix {
-                used_keys.insert(key.clone());
+
+    usage_index
+}
+
+/// Record every key named by a `hunt-keep:` / `i18n-keys:` annotation in a source file.
+/// Annotation entries use keep-list syntax, so `status.*` covers every status key
+fn record_annotations(
+    content: &str,
+    kind: Option<extract::SourceKind>,
+    file_path: &str,
+    translations: &std::collections::HashMap<String, Value>,
+    usage: &mut UsageIndex,
+) {
+    let annotations = annotations::find_annotations(content, kind);
+    if annotations.is_empty() {
+        return;
+    }
+
+    let lines = extract::LineIndex::new(content);
+    for annotation in annotations {
+        let Ok(pattern) = KeepPattern::new(&annotation.pattern) else {
+            continue;
+        };
+        let (line, column) = lines.position(annotation.offset);
+        for key in translations.keys().filter(|key| pattern.matches(key)) {
+            usage.record(
+                key,
+                UsageLocation {
+                    file: file_path.to_string(),
+                    line,
+                    column,
+                    kind: MatchKind::Annotation,
+                },
+            );
+        }
+    }
+}
+
+/// A literal key passed to a translation call site that has no translation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingKey {
+    pub key: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Find literal keys used at translation call sites that do not exist in the translations.
+/// Keys naming a whole object or array of translations (`returnObjects`) are not missing
+pub fn find_missing_keys(
+    translations: &std::collections::HashMap<String, Value>,
+    source_files: &[String],
+    options: &SearchOptions,
+) -> Vec<MissingKey> {
+    let pb = create_progress_bar();
+    pb.set_message("The lion is tracking missing keys…");
+    pb.enable_steady_tick(std::time::Duration::from_millis(50));
+
+    let mut missing = Vec::new();
+    // Base keys only exist through their plural and context variants
+    let variant_groups = options.variants.groups(translations.keys());
+
+    for file_path in source_files {
+        let path = std::path::Path::new(file_path);
+        let kind = match options.scanners.scanner_for(path) {
+            // Plain-text files have no call sites to inspect
+            Some(Scanner::Text) | None => continue,
+            Some(scanner) => scanner.source_kind(path),
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+
+        for usage in extract::extract_usages(&content, kind, &options.call_sites) {
+            let KeyUsage::Literal(key) = usage.key else {
+                continue;
+            };
+            if translations.contains_key(&key) {
+                continue;
             }
+
+            let key = options.resolve_key(&key, usage.namespace.as_deref());
+            if translations.contains_key(&key)
+                || variant_groups.contains_key(&key)
+                || has_children(translations, &key)
+            {
+                continue;
+            }
+
+            missing.push(MissingKey {
+                key,
+                file: file_path.clone(),
+                line: usage.line,
+                column: usage.column,
+            });
         }
     }
-    
-    used_keys
+
+    pb.finish_and_clear();
+    missing
+}
+
+/// Whether some translation key is nested below `key` (`key.child` or `key[0]`)
+fn has_children(translations: &std::collections::HashMap<String, Value>, key: &str) -> bool {
+    translations.keys().any(|existing| {
+        existing.len() > key.len()
+            && existing.starts_with(key)
+            && matches!(existing.as_bytes()[key.len()], b'.' | b'[')
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plural::PluralFormat;
     use serde_json::json;
-    
+
+    fn search_options(default_namespace: Option<&str>) -> SearchOptions {
+        SearchOptions {
+            scanners: crate::scanner::build_scanner_map(&[]).unwrap(),
+            extraction: Extraction::Ast,
+            call_sites: CallSites::new(&[], &[]).unwrap(),
+            default_namespace: default_namespace.map(|ns| ns.to_string()),
+            collect_locations: false,
+            keep: KeepList::default(),
+            variants: Variants::new(PluralFormat::V4, &[]),
+        }
+    }
+
     fn create_temp_translations() -> std::collections::HashMap<String, serde_json::Value> {
         let mut map = std::collections::HashMap::new();
         map.insert("hello.world".to_string(), json!("Hello World"));
         map.insert("foo.bar".to_string(), json!("Foo Bar"));
         map
     }
-    
+
     #[test]
     fn test_compile_regex_patterns() {
         let translations = create_temp_translations();
-        let patterns = compile_regex_patterns(&translations);
-        
+        let patterns = compile_regex_patterns(&translations, &search_options(None));
+
         assert_eq!(patterns.len(), 2);
+
+        let element = Regex::new(&whole_key_pattern("steps[0]")).unwrap();
+        assert!(element.is_match("t(\"steps[0]\")"));
+        assert!(!element.is_match("t(\"prosteps[0]\")"));
     }
-    
+
     #[test]
-    fn test_extract_base_prefixes() {
+    fn test_key_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("App.tsx");
+        fs::write(&file, "t(`errors.${code}.title`);\nt('status.' + item.status, { ns: 'App' });\nReimbursement:expenseCategory.${category}").unwrap();
+        let types = root.join("item.ts");
+        fs::write(
+            &types,
+            "type Status = 'open' | 'closed';\ninterface Item { status: Status }\n",
+        )
+        .unwrap();
+
         let mut translations = std::collections::HashMap::new();
-        translations.insert("expenseCategory.foo".to_string(), json!("Foo"));
-        translations.insert("expenseCategory.bar".to_string(), json!("Bar"));
-        translations.insert("status.open".to_string(), json!("Open"));
-        translations.insert("status.closed".to_string(), json!("Closed"));
-        
-        let prefixes = extract_base_prefixes(&translations);
-        
-        assert!(prefixes.contains_key("expenseCategory"));
-        assert!(prefixes.contains_key("status"));
-        assert_eq!(prefixes.get("expenseCategory").unwrap().len(), 2);
-        assert_eq!(prefixes.get("status").unwrap().len(), 2);
-    }
-    
+        let keys = [
+            "errors.network.title",
+            "errors.network.body",
+            "status.open",
+            "status.open.hint",
+            "status.closed",
+            "status.archived",
+            "expenseCategory.foo",
+        ];
+        for key in keys {
+            translations.insert(key.to_string(), json!(key));
+        }
+        let files = vec![
+            file.to_string_lossy().to_string(),
+            types.to_string_lossy().to_string(),
+        ];
+        let mut options = search_options(None);
+
+        for extraction in [Extraction::Ast, Extraction::Regex] {
+            options.extraction = extraction;
+            let usage = check_translation_usage(&translations, &files, &options);
+            assert!(usage.contains("errors.network.title"));
+            assert!(!usage.contains("errors.network.body"));
+            assert!(usage.contains("status.open"));
+            assert!(!usage.contains("status.open.hint"));
+            // `item.status` is a `Status`, which cannot be "archived"
+            assert!(usage.contains("status.closed"));
+            assert!(!usage.contains("status.archived"));
+            assert_eq!(usage.locations("status.open")[0].line, 2);
+            assert_eq!(usage.locations("status.open")[0].kind, MatchKind::Dynamic);
+        }
+
+        // Outside of call sites, only the regex extraction finds keys
+        let usage = check_translation_usage(&translations, &files, &options);
+        assert!(usage.contains("expenseCategory.foo"));
+    }
+
     #[test]
-    fn test_dynamic_pattern_matching() {
-        let mut translations = std::collections::HashMap::new();
-        translations.insert("expenseCategory.foo".to_string(), json!("Foo"));
-        translations.insert("expenseCategory.bar".to_string(), json!("Bar"));
-        translations.insert("status.open".to_string(), json!("Open"));
-        
-        let prefixes = extract_base_prefixes(&translations);
-        let patterns = compile_dynamic_patterns(&prefixes);
-        
-        // Test that pattern matches dynamic usage
-        let test_code1 = "const key = `expenseCategory.${variable}`;";
-        let test_code2 = "t('status.${item.status}', { ns: 'App' });";
-        let test_code3 = "Reimbursement:expenseCategory.${reimbursement.expenseCategory}";
-        
-        let expense_category_pattern = patterns.iter()
-            .find(|(p, _)| p == "expenseCategory")
-            .map(|(_, re)| re);
-        let status_pattern = patterns.iter()
-            .find(|(p, _)| p == "status")
-            .map(|(_, re)| re);
-        
-        if let Some(pattern) = expense_category_pattern {
-            assert!(pattern.is_match(test_code1), "Should match template literal");
-            assert!(pattern.is_match(test_code3), "Should match namespace format");
+    fn test_discover_respects_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("src/generated")).unwrap();
+        fs::write(root.join("src/App.tsx"), "").unwrap();
+        fs::write(root.join("src/generated/keys.ts"), "").unwrap();
+        fs::write(root.join("src/.huntignore"), "generated/\n").unwrap();
+
+        let patterns = crate::ignore::load_ignore_patterns(&[]).unwrap();
+        let dirs = vec![root.to_string_lossy().to_string()];
+
+        let scanners = crate::scanner::build_scanner_map(&[]).unwrap();
+
+        let files = discover_source_files(&dirs, &patterns, &scanners, true).unwrap();
+        assert_eq!(files.len(), 1);
+        assert!(files[0].ends_with("App.tsx"));
+
+        let files = discover_source_files(&dirs, &patterns, &scanners, false).unwrap();
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_namespaced_keys() {
+        let options = search_options(Some("common"));
+
+        assert_eq!(
+            options.resolve_key("billing:invoice.title", Some("legal")),
+            "billing:invoice.title"
+        );
+        assert_eq!(
+            options.resolve_key("invoice.title", Some("billing")),
+            "billing:invoice.title"
+        );
+        assert_eq!(options.resolve_key("app.title", None), "common:app.title");
+
+        // Plain-text files only use other namespaces through qualified keys
+        let pattern = Regex::new(&options.key_pattern("common:app.title")).unwrap();
+        assert!(pattern.is_match("app.title") && pattern.is_match("common:app.title"));
+        let pattern = Regex::new(&options.key_pattern("billing:invoice.title")).unwrap();
+        assert!(pattern.is_match("billing:invoice.title") && !pattern.is_match("invoice.title"));
+
+        let options = search_options(None);
+        assert_eq!(
+            options.resolve_key("billing:invoice.title", None),
+            "invoice.title"
+        );
+    }
+
+    #[test]
+    fn test_find_missing_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("App.tsx");
+        fs::write(&file, "t('hello.world');\nt('hello.wrold');\nt('foo', { returnObjects: true });\nt(`foo.${x}`);").unwrap();
+
+        let translations = create_temp_translations();
+        let files = vec![file.to_string_lossy().to_string()];
+        let missing = find_missing_keys(&translations, &files, &search_options(None));
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].key, "hello.wrold");
+        assert_eq!((missing[0].line, missing[0].column), (2, 3));
+    }
+
+    #[test]
+    fn test_usage_locations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("App.tsx");
+        fs::write(
+            &file,
+            "t('hello.world');\nconst a = 'hello.world';\nt(`foo.${x}`);",
+        )
+        .unwrap();
+
+        let translations = create_temp_translations();
+        let files = vec![file.to_string_lossy().to_string()];
+        let mut options = search_options(None);
+        options.extraction = Extraction::Regex;
+
+        let usage = check_translation_usage(&translations, &files, &options);
+        assert_eq!(usage.locations("hello.world").len(), 1);
+
+        options.collect_locations = true;
+        let usage = check_translation_usage(&translations, &files, &options);
+        let locations = usage.locations("hello.world");
+        assert_eq!(locations.len(), 2);
+        assert_eq!((locations[1].line, locations[1].column), (2, 12));
+        assert_eq!(usage.locations("foo.bar")[0].kind, MatchKind::Dynamic);
+
+        options.extraction = Extraction::Ast;
+        let usage = check_translation_usage(&translations, &files, &options);
+        assert_eq!(usage.locations("hello.world").len(), 1);
+        assert_eq!((usage.locations("foo.bar")[0].line), 3);
+    }
+
+    #[test]
+    fn test_keep_list_counts_as_used() {
+        let translations = create_temp_translations();
+        let mut options = search_options(None);
+        options.keep = KeepList::new(&["foo.*".to_string()]).unwrap();
+
+        let usage = check_translation_usage(&translations, &[], &options);
+        assert!(usage.contains("foo.bar"));
+        assert!(!usage.contains("hello.world"));
+        assert_eq!(usage.kept_by("foo.bar"), Some("foo.*"));
+    }
+
+    #[test]
+    fn test_annotations_count_as_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("App.tsx");
+        fs::write(
+            &file,
+            "const label = t(key);\n// hunt-keep: foo.*\n/* i18n-keys: hello.world */",
+        )
+        .unwrap();
+
+        let translations = create_temp_translations();
+        let files = vec![file.to_string_lossy().to_string()];
+        let mut options = search_options(None);
+
+        for extraction in [Extraction::Ast, Extraction::Regex] {
+            options.extraction = extraction;
+            let usage = check_translation_usage(&translations, &files, &options);
+            let location = &usage.locations("foo.bar")[0];
+            assert_eq!(
+                (location.line, location.column, location.kind),
+                (2, 15, MatchKind::Annotation)
+            );
+            assert_eq!(usage.locations("hello.world")[0].line, 3);
         }
-        
-        if let Some(pattern) = status_pattern {
-            assert!(pattern.is_match(test_code2), "Should match function call with t()");
+    }
+
+    #[test]
+    fn test_plural_and_context_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("Cart.tsx");
+        fs::write(
+            &file,
+            "t('cart.items', { count });\nt('friend', { context: gender });\nt('hello.world');",
+        )
+        .unwrap();
+
+        let mut translations = create_temp_translations();
+        for key in [
+            "cart.items_one",
+            "cart.items_other",
+            "friend_male",
+            "friend_female_one",
+            "orders_other",
+            "hello.world_one",
+        ] {
+            translations.insert(key.to_string(), json!("text"));
         }
+        let files = vec![file.to_string_lossy().to_string()];
+        let mut options = search_options(None);
+        options.variants = Variants::new(
+            PluralFormat::V4,
+            &["male".to_string(), "female".to_string()],
+        );
+
+        for extraction in [Extraction::Ast, Extraction::Regex] {
+            options.extraction = extraction;
+            let usage = check_translation_usage(&translations, &files, &options);
+            let used = usage.used_keys();
+            for key in [
+                "cart.items_one",
+                "cart.items_other",
+                "friend_male",
+                "friend_female_one",
+                "hello.world",
+                "hello.world_one",
+            ] {
+                assert!(used.contains(key), "{} should be used", key);
+            }
+            assert_eq!(usage.locations("cart.items_other")[0].line, 1);
+            // Base keys without an entry of their own are not reported as used keys
+            assert!(!used.contains("cart.items") && !used.contains("friend"));
+            assert!(!used.contains("orders_other"));
+        }
+
+        assert!(find_missing_keys(&translations, &files, &options).is_empty());
     }
-}
 
+    #[test]
+    fn test_nested_references_count_as_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let file = root.join("App.tsx");
+        fs::write(&file, "t('hello.world');").unwrap();
+
+        let mut translations = create_temp_translations();
+        translations.insert("hello.world".to_string(), json!("Hello $t(greeting.name)"));
+        translations.insert(
+            "greeting.name".to_string(),
+            json!("@:user.name and $t(items, {\"count\": 2})"),
+        );
+        translations.insert("user.name".to_string(), json!("Name"));
+        translations.insert("items_one".to_string(), json!("item"));
+        translations.insert("unused.label".to_string(), json!("$t(orphan.key)"));
+        translations.insert("orphan.key".to_string(), json!("Orphan"));
+        let files = vec![file.to_string_lossy().to_string()];
+
+        let usage = check_translation_usage(&translations, &files, &search_options(None));
+        assert_eq!(usage.referenced_by("greeting.name"), Some("hello.world"));
+        assert_eq!(usage.referenced_by("user.name"), Some("greeting.name"));
+        assert_eq!(usage.referenced_by("items_one"), Some("greeting.name"));
+        assert!(!usage.contains("unused.label") && !usage.contains("orphan.key"));
+    }
 
+    #[test]
+    fn test_whole_arrays_use_their_elements() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Steps.tsx");
+        fs::write(
+            &file,
+            "const steps = t('onboarding.steps', { returnObjects: true });",
+        )
+        .unwrap();
+
+        let mut translations = create_temp_translations();
+        for key in [
+            "onboarding.steps[0]",
+            "onboarding.steps[1].title",
+            "onboarding.tips[0]",
+        ] {
+            translations.insert(key.to_string(), json!("text"));
+        }
+        let files = vec![file.to_string_lossy().to_string()];
+        let mut options = search_options(None);
+
+        for extraction in [Extraction::Ast, Extraction::Regex] {
+            options.extraction = extraction;
+            let used = check_translation_usage(&translations, &files, &options).used_keys();
+            assert!(
+                used.contains("onboarding.steps[0]") && used.contains("onboarding.steps[1].title")
+            );
+            assert!(!used.contains("onboarding.steps") && !used.contains("onboarding.tips[0]"));
+        }
+
+        // Using one element leaves the others unused
+        fs::write(&file, "t('onboarding.steps[1].title')").unwrap();
+        for extraction in [Extraction::Ast, Extraction::Regex] {
+            options.extraction = extraction;
+            let used = check_translation_usage(&translations, &files, &options).used_keys();
+            assert!(used.contains("onboarding.steps[1].title"));
+            assert!(!used.contains("onboarding.steps[0]"));
+        }
+    }
+}
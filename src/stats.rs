@@ -6,6 +6,7 @@ pub struct HuntStats {
     pub files_total: usize,
     pub keys_total: usize,
     pub unused_keys_count: usize,
+    pub missing_keys_count: usize,
     pub duration: Duration,
 }
 
@@ -0,0 +1,325 @@
+//! Keys built at runtime, such as `` `errors.${code}.title` ``, `'status.' + code` or
+//! `['menu', id, 'label'].join('.')`, described as templates with a wildcard for every part
+//! that is only known at runtime.
+
+use crate::translation::{split_namespace, NAMESPACE_SEPARATOR};
+use regex::Regex;
+
+/// Stands for an interpolated or concatenated value in a template
+pub const WILDCARD: char = '*';
+
+/// A string literal (single, double or backtick quoted)
+const STRING: &str = r#"'(?:[^'\\\n]|\\.)*'|"(?:[^"\\\n]|\\.)*"|`[^`]*`"#;
+
+/// An identifier, member chain, call or index expression
+const EXPRESSION: &str = r"[\w$]+(?:\??\.[\w$]+|\([^()\n]*\)|\[[^\[\]\n]*\])*";
+
+/// Piece of a key built at runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Literal(String),
+    /// A value only known at runtime, with the source of the expression producing it
+    Value(String),
+}
+
+/// The unknown part of a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Wildcard {
+    /// Source of the expression producing the value; empty when not known
+    expression: String,
+    /// Values the expression can take, when its type is known
+    values: Option<Vec<String>>,
+}
+
+/// A key with `*` for every part only known at runtime: `errors.*.title`.
+/// A wildcard stands for one key segment (or namespace), or part of one, unless it has been
+/// narrowed to the values of its expression's type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyTemplate {
+    pattern: String,
+    wildcards: Vec<Wildcard>,
+}
+
+impl KeyTemplate {
+    /// Template of the parts; adjacent values form one wildcard. Parts without a value, or
+    /// without any known letter or digit (`${a}.${b}`), are not templates
+    pub fn from_parts(parts: &[TemplatePart]) -> Option<Self> {
+        let mut pattern = String::new();
+        let mut wildcards: Vec<Wildcard> = Vec::new();
+        for part in parts {
+            match part {
+                TemplatePart::Literal(text) => pattern.push_str(text),
+                // Adjacent values form one wildcard whose expression is unknown
+                TemplatePart::Value(_) if pattern.ends_with(WILDCARD) => {
+                    if let Some(last) = wildcards.last_mut() {
+                        last.expression.clear();
+                    }
+                }
+                TemplatePart::Value(expression) => {
+                    pattern.push(WILDCARD);
+                    wildcards.push(Wildcard {
+                        expression: expression.clone(),
+                        values: None,
+                    });
+                }
+            }
+        }
+
+        let known = pattern.chars().any(|c| c.is_alphanumeric());
+        (!wildcards.is_empty() && known).then_some(KeyTemplate { pattern, wildcards })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// The template with every wildcard whose expression has known values restricted to them
+    pub fn narrow(&self, values_of: impl Fn(&str) -> Option<Vec<String>>) -> KeyTemplate {
+        let mut narrowed = self.clone();
+        for wildcard in &mut narrowed.wildcards {
+            if wildcard.values.is_none() && !wildcard.expression.is_empty() {
+                wildcard.values = values_of(&wildcard.expression).filter(|v| !v.is_empty());
+            }
+        }
+        narrowed
+    }
+
+    /// The template with its namespace part replaced, or dropped with `None`
+    pub fn with_namespace(&self, namespace: Option<&str>) -> KeyTemplate {
+        let (current, rest) = split_namespace(&self.pattern);
+        let dropped = current.map_or(0, |current| current.matches(WILDCARD).count());
+        let pattern = match namespace {
+            Some(namespace) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, rest),
+            None => rest.to_string(),
+        };
+        KeyTemplate {
+            pattern,
+            wildcards: self.wildcards[dropped..].to_vec(),
+        }
+    }
+
+    /// Source of the regex matching exactly the keys the template can produce
+    pub fn regex_source(&self) -> String {
+        let mut source = String::from("^");
+        for (i, literal) in self.pattern.split(WILDCARD).enumerate() {
+            if let Some(wildcard) = i.checked_sub(1).and_then(|i| self.wildcards.get(i)) {
+                match &wildcard.values {
+                    Some(values) => {
+                        let values: Vec<String> =
+                            values.iter().map(|value| regex::escape(value)).collect();
+                        source.push_str(&format!("(?:{})", values.join("|")));
+                    }
+                    None => source.push_str("[^.:]+"),
+                }
+            }
+            source.push_str(&regex::escape(literal));
+        }
+        source.push('$');
+        source
+    }
+
+    pub fn regex(&self) -> Regex {
+        Regex::new(&self.regex_source()).expect("escaped template is valid")
+    }
+}
+
+/// Finds key templates in plain source text, for when files are not tokenized
+pub struct TemplateFinder {
+    /// Keys with `${...}` interpolations, quoted or not
+    interpolation: Regex,
+    concatenation: Regex,
+    join: Regex,
+    operand: Regex,
+    placeholder: Regex,
+}
+
+impl TemplateFinder {
+    pub fn new() -> Self {
+        let operand = format!("(?:{}|{})", STRING, EXPRESSION);
+        TemplateFinder {
+            interpolation: Regex::new(r"[\w.:-]*(?:\$\{[^{}]*\}[\w.:-]*)+").unwrap(),
+            concatenation: Regex::new(&format!(r"{}(?:\s*\+\s*{})+", operand, operand)).unwrap(),
+            join: Regex::new(r#"\[([^\[\]]*)\]\s*\.join\(\s*(?:'([^'\n]*)'|"([^"\n]*)")\s*\)"#)
+                .unwrap(),
+            operand: Regex::new(&operand).unwrap(),
+            placeholder: Regex::new(r"\$\{[^{}]*\}").unwrap(),
+        }
+    }
+
+    /// Templates in `content` with the byte offset where each starts. Only templates containing a
+    /// key separator (`.` or `:`) are kept, since any text could otherwise pass for a key
+    pub fn find(&self, content: &str) -> Vec<(usize, KeyTemplate)> {
+        let mut found: Vec<(usize, usize, Vec<TemplatePart>)> = Vec::new();
+
+        for captures in self.join.captures_iter(content) {
+            let whole = captures.get(0).unwrap();
+            let separator = captures
+                .get(2)
+                .or(captures.get(3))
+                .map_or("", |m| m.as_str());
+            let mut parts = Vec::new();
+            for (i, element) in self.operand.find_iter(&captures[1]).enumerate() {
+                if i > 0 {
+                    parts.push(TemplatePart::Literal(separator.to_string()));
+                }
+                parts.extend(self.operand_parts(element.as_str()));
+            }
+            found.push((whole.start(), whole.end(), parts));
+        }
+
+        for m in self.concatenation.find_iter(content) {
+            if overlaps(&found, m.start(), m.end()) {
+                continue;
+            }
+            let parts = self
+                .operand
+                .find_iter(m.as_str())
+                .flat_map(|operand| self.operand_parts(operand.as_str()))
+                .collect();
+            found.push((m.start(), m.end(), parts));
+        }
+
+        // Interpolations that are not part of a larger construct
+        for m in self.interpolation.find_iter(content) {
+            let text = m.as_str().trim_end_matches(['.', ':']);
+            let trimmed = text.trim_start_matches(['.', ':']);
+            let start = m.start() + text.len() - trimmed.len();
+            if overlaps(&found, start, start + trimmed.len()) {
+                continue;
+            }
+            found.push((
+                start,
+                start + trimmed.len(),
+                self.interpolated_parts(trimmed),
+            ));
+        }
+
+        found.sort_by_key(|(start, _, _)| *start);
+        found
+            .into_iter()
+            .filter_map(|(start, _, parts)| {
+                let template = KeyTemplate::from_parts(&parts)?;
+                template
+                    .as_str()
+                    .contains(['.', ':'])
+                    .then_some((start, template))
+            })
+            .collect()
+    }
+
+    /// Parts of one operand: the contents of a string literal, or a wildcard for an expression
+    fn operand_parts(&self, operand: &str) -> Vec<TemplatePart> {
+        let quoted = operand.len() >= 2 && operand.starts_with(['\'', '"', '`']);
+        if !quoted {
+            return vec![TemplatePart::Value(operand.to_string())];
+        }
+
+        let inner = &operand[1..operand.len() - 1];
+        if operand.starts_with('`') {
+            self.interpolated_parts(inner)
+        } else {
+            vec![TemplatePart::Literal(inner.to_string())]
+        }
+    }
+
+    /// Parts of text with `${...}` placeholders
+    fn interpolated_parts(&self, text: &str) -> Vec<TemplatePart> {
+        let mut parts = Vec::new();
+        let mut last = 0;
+        for placeholder in self.placeholder.find_iter(text) {
+            parts.push(TemplatePart::Literal(
+                text[last..placeholder.start()].to_string(),
+            ));
+            let expression = &placeholder.as_str()[2..placeholder.len() - 1];
+            parts.push(TemplatePart::Value(expression.trim().to_string()));
+            last = placeholder.end();
+        }
+        parts.push(TemplatePart::Literal(text[last..].to_string()));
+        parts
+    }
+}
+
+impl Default for TemplateFinder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn overlaps(found: &[(usize, usize, Vec<TemplatePart>)], start: usize, end: usize) -> bool {
+    found
+        .iter()
+        .any(|(other_start, other_end, _)| start < *other_end && *other_start < end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn templates(content: &str) -> Vec<String> {
+        TemplateFinder::new()
+            .find(content)
+            .into_iter()
+            .map(|(_, template)| template.as_str().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_find_templates() {
+        assert_eq!(
+            templates("t(`errors.${code}.title`); t('status.' + item.code);"),
+            ["errors.*.title", "status.*"]
+        );
+        assert_eq!(
+            templates("t('errors.' + code + '.title'); t(['menu', id, 'label'].join('.'))"),
+            ["errors.*.title", "menu.*.label"]
+        );
+        assert_eq!(
+            templates("t(`${ns}:foo.bar`); Reimbursement:expenseCategory.${category}"),
+            ["*:foo.bar", "Reimbursement:expenseCategory.*"]
+        );
+
+        // Not keys: no known text, or no key separator
+        assert!(templates("`${a}.${b}`; 'Hello ' + name; `${size}px`; a + b").is_empty());
+    }
+
+    /// Template of a pattern using `*` for values
+    fn template(pattern: &str) -> Option<KeyTemplate> {
+        let mut parts = Vec::new();
+        for (i, literal) in pattern.split(WILDCARD).enumerate() {
+            if i > 0 {
+                parts.push(TemplatePart::Value(String::new()));
+            }
+            parts.push(TemplatePart::Literal(literal.to_string()));
+        }
+        KeyTemplate::from_parts(&parts)
+    }
+
+    #[test]
+    fn test_template_matching() {
+        let regex = template("errors.*.title").unwrap().regex();
+        assert!(regex.is_match("errors.network.title"));
+        assert!(!regex.is_match("errors.network.body"));
+        assert!(!regex.is_match("errors.network.retry.title"));
+
+        assert!(template("*:foo.bar")
+            .unwrap()
+            .regex()
+            .is_match("common:foo.bar"));
+        assert_eq!(template("a.**").unwrap().as_str(), "a.*");
+        assert!(template("a.b").is_none());
+
+        let parts = [
+            TemplatePart::Literal("status.".to_string()),
+            TemplatePart::Value("item.status".to_string()),
+        ];
+        let narrowed = KeyTemplate::from_parts(&parts)
+            .unwrap()
+            .narrow(|expression| {
+                (expression == "item.status")
+                    .then(|| vec!["open".to_string(), "closed".to_string()])
+            })
+            .regex();
+        assert!(narrowed.is_match("status.open"));
+        assert!(!narrowed.is_match("status.archived"));
+    }
+}
@@ -1,139 +1,422 @@
+use regex::Regex;
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-/// Load translation files from a path (can be a file or directory)
-pub fn load_translations(path: &str) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+/// Separator between namespace and key in qualified keys (`billing:invoice.title`)
+pub const NAMESPACE_SEPARATOR: char = ':';
+
+/// Split a qualified key into its namespace and the key within the namespace
+pub fn split_namespace(key: &str) -> (Option<&str>, &str) {
+    match key.split_once(NAMESPACE_SEPARATOR) {
+        Some((namespace, rest)) if !namespace.is_empty() && !rest.is_empty() => {
+            (Some(namespace), rest)
+        }
+        _ => (None, key),
+    }
+}
+
+/// Namespace of a translation file: its file name without extension (`billing.json` -> `billing`)
+fn file_namespace(path: &Path) -> Option<&str> {
+    path.file_stem().and_then(|stem| stem.to_str())
+}
+
+/// How arrays in translation files map to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayMode {
+    /// Every element is its own key (`items[0]`) and unused elements are removed
+    Elements,
+    /// The whole array is one key named after its parent (`items`), as read with `returnObjects`
+    Atomic,
+}
+
+impl ArrayMode {
+    pub fn from_name(name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match name {
+            "elements" => Ok(ArrayMode::Elements),
+            "atomic" => Ok(ArrayMode::Atomic),
+            _ => Err(format!(
+                "Unknown array mode '{}' (expected elements or atomic)",
+                name
+            )
+            .into()),
+        }
+    }
+}
+
+/// Flattened translations with where every key is defined
+pub type LocatedTranslations = (HashMap<String, Value>, HashMap<String, KeyLocation>);
+
+/// Load translation files from a path (can be a file or directory).
+/// With `namespaced`, keys are qualified with their file's namespace (`common:app.title`)
+pub fn load_translations(
+    path: &str,
+    namespaced: bool,
+    arrays: ArrayMode,
+) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+    load_located_translations(path, namespaced, arrays).map(|(translations, _)| translations)
+}
+
+/// Load translations like `load_translations`, along with the file, line and column of every key
+pub fn load_located_translations(
+    path: &str,
+    namespaced: bool,
+    arrays: ArrayMode,
+) -> Result<LocatedTranslations, Box<dyn std::error::Error>> {
     let path = Path::new(path);
 
     if path.is_dir() {
-        load_translations_from_dir(path)
+        load_translations_from_dir(path, namespaced, arrays)
     } else if path.is_file() {
-        load_translation_file(path.to_str().unwrap())
+        load_translation_file(path, namespaced, arrays)
     } else {
         Err(format!("Path does not exist: {}", path.display()).into())
     }
 }
 
+/// Locales found in a locales root: `{root}/{lng}/*.json` directories or `{root}/{lng}.json` files,
+/// sorted by locale name
+pub fn find_locales(root: &str) -> Result<Vec<(String, PathBuf)>, Box<dyn std::error::Error>> {
+    let root = Path::new(root);
+    if !root.is_dir() {
+        return Err(format!("Locales root is not a directory: {}", root.display()).into());
+    }
+
+    let mut locales = Vec::new();
+    for entry in fs::read_dir(root)? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string())
+        else {
+            continue;
+        };
+
+        let is_locale_dir = path.is_dir() && contains_json_files(&path)?;
+        let is_locale_file =
+            path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json");
+        if is_locale_dir || is_locale_file {
+            locales.push((name, path));
+        }
+    }
+
+    locales.sort();
+    Ok(locales)
+}
+
+/// Path holding the translations of `locale` inside a locales root
+pub fn locale_path(root: &str, locale: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    find_locales(root)?
+        .into_iter()
+        .find(|(name, _)| name == locale)
+        .map(|(_, path)| path)
+        .ok_or_else(|| {
+            format!(
+                "Source locale '{}' not found in {} (expected {}/{}/ or {}/{}.json)",
+                locale, root, root, locale, root, locale
+            )
+            .into()
+        })
+}
+
+fn contains_json_files(dir: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Load and merge all JSON files from a directory
 fn load_translations_from_dir(
     dir: &Path,
-) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+    namespaced: bool,
+    arrays: ArrayMode,
+) -> Result<LocatedTranslations, Box<dyn std::error::Error>> {
     let mut all_translations = HashMap::new();
-    let mut found_files = false;
+    let mut all_locations = HashMap::new();
 
-    // Get all JSON files in the directory
-    let entries = fs::read_dir(dir)?;
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
+    for path in json_files(dir)? {
+        let (translations, locations) = load_translation_file(&path, namespaced, arrays)?;
 
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
-            if let Some(path_str) = path.to_str() {
-                let translations = load_translation_file(path_str)?;
+        // Merge with existing translations (later files override earlier ones)
+        all_translations.extend(translations);
+        all_locations.extend(locations);
+    }
 
-                // Merge with existing translations (later files override earlier ones)
-                for (key, value) in translations {
-                    all_translations.insert(key, value);
-                }
+    Ok((all_translations, all_locations))
+}
 
-                found_files = true;
-            }
+/// JSON files directly inside a directory
+fn json_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+            files.push(path);
         }
     }
 
-    if !found_files {
+    if files.is_empty() {
         return Err(format!("No JSON files found in directory: {}", dir.display()).into());
     }
 
-    Ok(all_translations)
+    Ok(files)
+}
+
+/// Where a translation key is defined: its file and the 1-based line and column of its property name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
 }
 
-/// Load and flatten translation keys from a single JSON file
+/// Load and flatten translation keys from a single JSON file. The file is parsed once, keeping
+/// the position of every key
 fn load_translation_file(
-    file_path: &str,
-) -> Result<HashMap<String, Value>, Box<dyn std::error::Error>> {
+    file_path: &Path,
+    namespaced: bool,
+    arrays: ArrayMode,
+) -> Result<LocatedTranslations, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(file_path)?;
-    let json: Value = serde_json::from_str(&content)?;
+    let flattened = flatten_json(&content, arrays)
+        .map_err(|e| format!("Failed to parse {}: {}", file_path.display(), e))?;
+    let lines = crate::extract::LineIndex::new(&content);
+    let file_name = file_path.to_string_lossy().to_string();
+    let namespace = file_namespace(file_path).filter(|_| namespaced);
+
+    let mut translations = HashMap::new();
+    let mut locations = HashMap::new();
+    for FlatKey { key, value, start } in flattened {
+        let key = match namespace {
+            Some(namespace) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, key),
+            None => key,
+        };
+        let (line, column) = lines.position(start);
+        translations.insert(key.clone(), value);
+        locations.insert(
+            key,
+            KeyLocation {
+                file: file_name.clone(),
+                line,
+                column,
+            },
+        );
+    }
 
-    Ok(flatten_json(json, String::new()))
+    Ok((translations, locations))
 }
 
-/// Flatten a nested JSON structure into dot-notation keys
-pub fn flatten_json(value: Value, prefix: String) -> HashMap<String, Value> {
-    let mut result = HashMap::new();
+/// A key of a flattened JSON document
+struct FlatKey {
+    key: String,
+    value: Value,
+    /// Offset of the property name (or of the value, for array elements)
+    start: usize,
+}
 
-    match value {
-        Value::Object(map) => {
-            for (key, val) in map {
-                let new_prefix = if prefix.is_empty() {
-                    key
-                } else {
-                    format!("{}.{}", prefix, key)
-                };
+/// Flatten a JSON document into dot-notation keys
+fn flatten_json(
+    content: &str,
+    arrays: ArrayMode,
+) -> Result<Vec<FlatKey>, Box<dyn std::error::Error>> {
+    let root = crate::json_spans::parse(content)?;
+    crate::json_spans::flatten_spans(&root, arrays)
+        .into_iter()
+        .map(|(key, span)| {
+            Ok(FlatKey {
+                key,
+                value: serde_json::from_str(&content[span.value])?,
+                start: span.start,
+            })
+        })
+        .collect()
+}
 
-                let sub_keys = flatten_json(val, new_prefix);
-                result.extend(sub_keys);
+/// i18next nesting: `$t(key)`, `$t(ns:key, { "count": 2 })`
+const NESTING_PATTERN: &str = r#"\$t\(\s*['"]?([^\s,()'"]+)"#;
+
+/// vue-i18n linked messages: `@:key`, `@.lower:key`, `@:(key)`
+const LINKED_PATTERN: &str = r"@(?:\.\w+)?:(?:\(([^()\s]+)\)|([\w\-.]*[\w\-]))";
+
+/// Keys referenced from the values of other keys, by referencing key. References without a
+/// namespace point into the namespace of the referencing key
+pub fn find_references(
+    translations: &HashMap<String, Value>,
+    namespaced: bool,
+) -> HashMap<String, Vec<String>> {
+    let nesting = Regex::new(NESTING_PATTERN).unwrap();
+    let linked = Regex::new(LINKED_PATTERN).unwrap();
+    let mut references = HashMap::new();
+
+    for (key, value) in translations {
+        let mut targets: Vec<String> = Vec::new();
+        for text in strings_of(value) {
+            let found = nesting
+                .captures_iter(text)
+                .chain(linked.captures_iter(text))
+                .filter_map(|captures| captures.iter().skip(1).flatten().next());
+            for target in found {
+                let (target_namespace, bare_target) = split_namespace(target.as_str());
+                let target = match split_namespace(key).0.filter(|_| namespaced) {
+                    Some(namespace) => format!(
+                        "{}{}{}",
+                        target_namespace.unwrap_or(namespace),
+                        NAMESPACE_SEPARATOR,
+                        bare_target
+                    ),
+                    None => bare_target.to_string(),
+                };
+                if target != *key && !targets.contains(&target) {
+                    targets.push(target);
+                }
             }
         }
-        Value::String(_) => {
-            result.insert(prefix, value);
-        }
-        Value::Number(_) => {
-            result.insert(prefix, value);
-        }
-        Value::Bool(_) => {
-            result.insert(prefix, value);
-        }
-        Value::Null => {
-            result.insert(prefix, value);
-        }
-        Value::Array(arr) => {
-            for (i, val) in arr.into_iter().enumerate() {
-                let new_prefix = format!("{}[{}]", prefix, i);
-                let sub_keys = flatten_json(val, new_prefix);
-                result.extend(sub_keys);
-            }
+        if !targets.is_empty() {
+            references.insert(key.clone(), targets);
         }
     }
 
-    result
+    references
+}
+
+/// Strings of a translation value, including those of arrays kept whole
+fn strings_of(value: &Value) -> Vec<&str> {
+    match value {
+        Value::String(text) => vec![text.as_str()],
+        Value::Array(values) => values.iter().flat_map(strings_of).collect(),
+        Value::Object(map) => map.values().flat_map(strings_of).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A translation file rewritten by `--clear`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub original: String,
+    pub updated: String,
+    /// Keys cut out of the file, in document order
+    pub removed: Vec<RemovedKey>,
+    /// Unused array elements left in the file so that a later element keeps its index
+    pub kept: Vec<String>,
+}
+
+/// A key removed from a translation file, with what it takes to put it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedKey {
+    /// Key as reported, qualified with its namespace when namespaced
+    pub key: String,
+    /// Position of the key in the original file
+    pub segments: Vec<crate::json_edit::PathSegment>,
+    pub value: Value,
 }
 
-/// Remove unused keys from translation files while preserving order
-pub fn remove_unused_keys(
+/// How `--clear` rewrites translation files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CleanupOptions {
+    /// Keys are qualified and each file only loses keys of its own namespace
+    pub namespaced: bool,
+    /// Remove objects and arrays left empty by the removal
+    pub prune_empty: bool,
+}
+
+/// Compute the cleaned content of every translation file that would change, without writing anything.
+/// Order is preserved
+pub fn plan_unused_key_removal(
     translation_path: &str,
     unused_keys: &[String],
     used_keys: &HashSet<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    options: CleanupOptions,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
     let path = Path::new(translation_path);
 
     if path.is_dir() {
-        remove_unused_from_directory(path, unused_keys, used_keys)
+        remove_unused_from_directory(path, unused_keys, used_keys, options)
     } else if path.is_file() {
-        remove_unused_from_file(path, unused_keys, used_keys)
+        Ok(
+            remove_unused_from_file(path, unused_keys, used_keys, options)?
+                .into_iter()
+                .collect(),
+        )
     } else {
         Err(format!("Path does not exist: {}", path.display()).into())
     }
 }
 
-/// Remove unused keys from a single JSON file
+/// Write planned changes to disk
+pub fn apply_changes(changes: &[FileChange]) -> Result<(), Box<dyn std::error::Error>> {
+    for change in changes {
+        fs::write(&change.path, &change.updated)?;
+    }
+    Ok(())
+}
+
+/// Remove unused keys from a single JSON file; None when the file does not change
 fn remove_unused_from_file(
     file_path: &Path,
     unused_keys: &[String],
     used_keys: &HashSet<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    options: CleanupOptions,
+) -> Result<Option<FileChange>, Box<dyn std::error::Error>> {
     let content = fs::read_to_string(file_path)?;
-    let json: Value = serde_json::from_str(&content)?;
 
-    let cleaned_json = remove_keys_from_value(json, unused_keys, used_keys)?;
+    let namespace = file_namespace(file_path).filter(|_| options.namespaced);
+    let edit = match namespace {
+        Some(namespace) => {
+            let unused_keys: Vec<String> = strip_namespace(unused_keys, namespace).collect();
+            let used_keys: HashSet<String> = strip_namespace(used_keys, namespace).collect();
+            remove_keys_from_source(&content, &unused_keys, &used_keys, options.prune_empty)
+        }
+        None => remove_keys_from_source(&content, unused_keys, used_keys, options.prune_empty),
+    }
+    .map_err(|e| format!("Failed to parse {}: {}", file_path.display(), e))?;
+
+    if edit.text == content {
+        return Ok(None);
+    }
 
-    // Write back with pretty formatting and trailing newline (standard for code files)
-    let updated_content = serde_json::to_string_pretty(&cleaned_json)?;
-    fs::write(file_path, format!("{}\n", updated_content))?;
+    let qualify = |path: String| match namespace {
+        Some(namespace) => format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, path),
+        None => path,
+    };
+    let removed = edit
+        .removed
+        .into_iter()
+        .map(|entry| {
+            Ok(RemovedKey {
+                key: qualify(entry.path),
+                segments: entry.segments,
+                value: serde_json::from_str(&content[entry.value])?,
+            })
+        })
+        .collect::<Result<_, serde_json::Error>>()?;
+
+    Ok(Some(FileChange {
+        path: file_path.to_path_buf(),
+        original: content,
+        updated: edit.text,
+        removed,
+        kept: edit.kept.into_iter().map(qualify).collect(),
+    }))
+}
 
-    Ok(())
+/// Keys of one namespace, without their `namespace:` qualifier
+fn strip_namespace<'a>(
+    keys: impl IntoIterator<Item = &'a String> + 'a,
+    namespace: &'a str,
+) -> impl Iterator<Item = String> + 'a {
+    keys.into_iter()
+        .filter_map(move |key| match split_namespace(key) {
+            (Some(ns), rest) if ns == namespace => Some(rest.to_string()),
+            _ => None,
+        })
 }
 
 /// Remove unused keys from directory of JSON files
@@ -141,7 +424,9 @@ fn remove_unused_from_directory(
     dir: &Path,
     unused_keys: &[String],
     used_keys: &HashSet<String>,
-) -> Result<(), Box<dyn std::error::Error>> {
+    options: CleanupOptions,
+) -> Result<Vec<FileChange>, Box<dyn std::error::Error>> {
+    let mut changes = Vec::new();
     let entries = fs::read_dir(dir)?;
 
     for entry in entries {
@@ -149,117 +434,87 @@ fn remove_unused_from_directory(
         let path = entry.path();
 
         if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
-            remove_unused_from_file(&path, unused_keys, used_keys)?;
+            changes.extend(remove_unused_from_file(
+                &path,
+                unused_keys,
+                used_keys,
+                options,
+            )?);
         }
     }
 
-    Ok(())
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
 }
 
-/// Recursively remove unused keys from a JSON value
-fn remove_keys_from_value(
-    value: Value,
-    unused_keys: &[String],
+/// Check whether a key (or object path) survives cleanup
+fn should_keep_key(
+    key_path: &str,
     used_keys: &HashSet<String>,
-) -> Result<Value, Box<dyn std::error::Error>> {
-    let unused_set: HashSet<&str> = unused_keys.iter().map(|s| s.as_str()).collect();
+    unused_set: &HashSet<&str>,
+) -> bool {
+    // Check if this exact key is used
+    if used_keys.contains(key_path) {
+        return true;
+    }
 
-    fn should_keep_key(
-        key_path: &str,
-        used_keys: &HashSet<String>,
-        unused_set: &HashSet<&str>,
-    ) -> bool {
-        // Check if this exact key is used
-        if used_keys.contains(key_path) {
+    // Check if any child key is used (e.g., if "user.name" is used, keep "user")
+    for used_key in used_keys {
+        if used_key.starts_with(&format!("{}.", key_path)) || used_key == key_path {
             return true;
         }
-
-        // Check if any child key is used (e.g., if "user.name" is used, keep "user")
-        for used_key in used_keys {
-            if used_key.starts_with(&format!("{}.", key_path)) || used_key == key_path {
-                return true;
-            }
-        }
-
-        // Key is not in unused list, so keep it
-        !unused_set.contains(key_path)
     }
 
-    fn remove_recursive(
-        value: Value,
-        prefix: String,
-        used_keys: &HashSet<String>,
-        unused_set: &HashSet<&str>,
-    ) -> Result<Value, Box<dyn std::error::Error>> {
-        match value {
-            Value::Object(map) => {
-                let mut cleaned_map = serde_json::Map::new();
-
-                for (key, val) in map {
-                    let current_path = if prefix.is_empty() {
-                        key.clone()
-                    } else {
-                        format!("{}.{}", prefix, key)
-                    };
-
-                    // Recursively clean nested values
-                    let cleaned_value =
-                        remove_recursive(val, current_path.clone(), used_keys, unused_set)?;
-
-                    // Only keep this key if it or any child is used
-                    if should_keep_key(&current_path, used_keys, unused_set) {
-                        cleaned_map.insert(key, cleaned_value);
-                    }
-                }
-
-                Ok(Value::Object(cleaned_map))
-            }
-            Value::Array(arr) => {
-                let mut cleaned_arr = Vec::new();
-
-                for (i, val) in arr.into_iter().enumerate() {
-                    let item_path = if prefix.is_empty() {
-                        format!("[{}]", i)
-                    } else {
-                        format!("{}[{}]", prefix, i)
-                    };
-
-                    let cleaned_value =
-                        remove_recursive(val, item_path.clone(), used_keys, unused_set)?;
-
-                    // Only keep array items if they or their children are used
-                    if should_keep_key(&item_path, used_keys, unused_set) {
-                        cleaned_arr.push(cleaned_value);
-                    } else {
-                        // Keep the slot to preserve array structure, but we'll filter empty arrays
-                        cleaned_arr.push(cleaned_value);
-                    }
-                }
-
-                // Filter out null values from cleaned arrays
-                let filtered_arr: Vec<Value> =
-                    cleaned_arr.into_iter().filter(|v| !v.is_null()).collect();
+    // Key is not in unused list, so keep it
+    !unused_set.contains(key_path)
+}
 
-                Ok(Value::Array(filtered_arr))
-            }
-            _ => Ok(value),
-        }
-    }
+/// Remove unused keys from the source of a JSON file, leaving its formatting untouched.
+/// With `prune_empty`, objects and arrays emptied by the removal go too
+fn remove_keys_from_source(
+    content: &str,
+    unused_keys: &[String],
+    used_keys: &HashSet<String>,
+    prune_empty: bool,
+) -> Result<crate::json_edit::Edit, Box<dyn std::error::Error>> {
+    let root = crate::json_spans::parse(content)?;
+    let unused_set: HashSet<&str> = unused_keys.iter().map(|s| s.as_str()).collect();
 
-    remove_recursive(value, String::new(), used_keys, &unused_set)
+    // With atomic arrays no element path is ever unused, so arrays are only removed as a whole.
+    // An element is referenced by its index when it, or a key inside it, is used
+    Ok(crate::json_edit::remove_entries(
+        content,
+        &root,
+        &|path| !should_keep_key(path, used_keys, &unused_set),
+        &|path| {
+            used_keys.iter().any(|used| {
+                used.strip_prefix(path)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+            })
+        },
+        prune_empty,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn flatten(json: &Value, arrays: ArrayMode) -> HashMap<String, Value> {
+        flatten_json(&json.to_string(), arrays)
+            .unwrap()
+            .into_iter()
+            .map(|flat| (flat.key, flat.value))
+            .collect()
+    }
+
     #[test]
     fn test_flatten_simple_object() {
         let json = serde_json::json!({
             "hello": "world",
             "foo": "bar"
         });
-        let result = flatten_json(json, String::new());
+        let result = flatten(&json, ArrayMode::Elements);
 
         assert_eq!(result.len(), 2);
         assert!(result.contains_key("hello"));
@@ -274,7 +529,7 @@ mod tests {
                 "age": 30
             }
         });
-        let result = flatten_json(json, String::new());
+        let result = flatten(&json, ArrayMode::Elements);
 
         assert_eq!(result.len(), 2);
         assert!(result.contains_key("user.name"));
@@ -286,9 +541,192 @@ mod tests {
         let json = serde_json::json!({
             "items": ["a", "b"]
         });
-        let result = flatten_json(json, String::new());
+        let result = flatten(&json, ArrayMode::Elements);
 
         assert!(result.contains_key("items[0]"));
         assert!(result.contains_key("items[1]"));
+
+        let result = flatten(&json, ArrayMode::Atomic);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result["items"], serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_remove_array_elements() {
+        let source = "{\n  \"items\": [\n    \"a\",\n    \"b\",\n    { \"c\": \"C\" }\n  ],\n  \"steps\": [\"x\"]\n}\n";
+        let unused = vec![
+            "items[1]".to_string(),
+            "items[2].c".to_string(),
+            "steps[0]".to_string(),
+        ];
+        let used: HashSet<String> = ["items[0]".to_string()].into_iter().collect();
+
+        let cleaned = remove_keys_from_source(source, &unused, &used, true)
+            .unwrap()
+            .text;
+        assert_eq!(cleaned, "{\n  \"items\": [\n    \"a\"\n  ]\n}\n");
+
+        // Unused elements before one used by its index stay; without such an element they go
+        let steps = "{\"steps\": [\"a\", \"b\", \"c\", \"d\"]}";
+        let unused = vec!["steps[0]".to_string(), "steps[3]".to_string()];
+        let used: HashSet<String> = ["steps[1]".to_string()].into_iter().collect();
+        let edit = remove_keys_from_source(steps, &unused, &used, true).unwrap();
+        assert_eq!(edit.text, "{\"steps\": [\"a\", \"b\", \"c\"]}");
+        assert_eq!(edit.kept, ["steps[0]"]);
+
+        let edit = remove_keys_from_source(steps, &unused, &HashSet::new(), true).unwrap();
+        assert_eq!(edit.text, "{\"steps\": [\"b\", \"c\"]}");
+        assert!(edit.kept.is_empty());
+
+        // Atomic arrays are only removed as a whole
+        let used: HashSet<String> = ["items".to_string()].into_iter().collect();
+        let cleaned = remove_keys_from_source(source, &["steps".to_string()], &used, true)
+            .unwrap()
+            .text;
+        assert_eq!(
+            cleaned,
+            "{\n  \"items\": [\n    \"a\",\n    \"b\",\n    { \"c\": \"C\" }\n  ]\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_load_located_translations() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("common.json"),
+            "{\n  \"title\": \"Home\",\n  \"user\": {\n    \"title\": \"Profile\"\n  }\n}\n",
+        )
+        .unwrap();
+
+        let (translations, locations) =
+            load_located_translations(&root.to_string_lossy(), true, ArrayMode::Elements).unwrap();
+        assert_eq!(translations["common:user.title"], "Profile");
+        let location = &locations["common:user.title"];
+        assert!(location.file.ends_with("common.json"));
+        assert_eq!((location.line, location.column), (4, 5));
+        assert_eq!(locations["common:title"].line, 2);
+    }
+
+    #[test]
+    fn test_split_namespace() {
+        assert_eq!(
+            split_namespace("billing:invoice.title"),
+            (Some("billing"), "invoice.title")
+        );
+        assert_eq!(split_namespace("invoice.title"), (None, "invoice.title"));
+        assert_eq!(split_namespace(":odd"), (None, ":odd"));
+    }
+
+    #[test]
+    fn test_remove_keys_of_one_namespace() {
+        let unused = vec!["billing:old".to_string(), "common:old".to_string()];
+        let used: HashSet<String> = ["billing:kept".to_string()].into_iter().collect();
+
+        let unused_local: Vec<String> = strip_namespace(&unused, "billing").collect();
+        let used_local: HashSet<String> = strip_namespace(&used, "billing").collect();
+        assert_eq!(unused_local, vec!["old".to_string()]);
+
+        let json = "{ \"old\": \"x\", \"kept\": \"y\" }";
+        let cleaned = remove_keys_from_source(json, &unused_local, &used_local, true)
+            .unwrap()
+            .text;
+        assert_eq!(cleaned, "{ \"kept\": \"y\" }");
+    }
+
+    #[test]
+    fn test_remove_keys_preserves_formatting() {
+        let source = "{\r\n\t\"title\": \"Caf\\u00e9\",\r\n\t\"old\": \"x\",\r\n\t\"nested\": {\r\n\t\t\"old\": 1,\r\n\t\t\"kept\": \"\u{1f981}\"\r\n\t},\r\n\t\"last\": \"y\"\r\n}";
+        let unused = vec!["old".to_string(), "nested.old".to_string()];
+
+        let cleaned = remove_keys_from_source(source, &unused, &HashSet::new(), true)
+            .unwrap()
+            .text;
+        assert_eq!(
+            cleaned,
+            "{\r\n\t\"title\": \"Caf\\u00e9\",\r\n\t\"nested\": {\r\n\t\t\"kept\": \"\u{1f981}\"\r\n\t},\r\n\t\"last\": \"y\"\r\n}"
+        );
+
+        let cleaned = remove_keys_from_source(
+            "{\n    \"a\": 1,\n    \"old\": 2\n}\n",
+            &["old".to_string()],
+            &HashSet::new(),
+            true,
+        )
+        .unwrap()
+        .text;
+        assert_eq!(cleaned, "{\n    \"a\": 1\n}\n");
+    }
+
+    #[test]
+    fn test_prune_emptied_parents() {
+        let source = "{\n  \"settings\": {\n    \"legacy\": {\n      \"a\": \"A\",\n      \"b\": \"B\"\n    },\n    \"theme\": \"T\"\n  }\n}\n";
+        let unused = vec![
+            "settings.legacy.a".to_string(),
+            "settings.legacy.b".to_string(),
+        ];
+        let used: HashSet<String> = ["settings.theme".to_string()].into_iter().collect();
+
+        let pruned = remove_keys_from_source(source, &unused, &used, true)
+            .unwrap()
+            .text;
+        assert_eq!(
+            pruned,
+            "{\n  \"settings\": {\n    \"theme\": \"T\"\n  }\n}\n"
+        );
+
+        let kept = remove_keys_from_source(source, &unused, &used, false)
+            .unwrap()
+            .text;
+        assert!(kept.contains("\"legacy\": {}"));
+    }
+
+    #[test]
+    fn test_find_locales() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("en-US")).unwrap();
+        fs::create_dir_all(root.join("de-DE")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("en-US/common.json"), "{}").unwrap();
+        fs::write(root.join("de-DE/common.json"), "{}").unwrap();
+        fs::write(root.join("fr.json"), "{}").unwrap();
+        let root_str = root.to_string_lossy().to_string();
+
+        let locales: Vec<String> = find_locales(&root_str)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(locales, vec!["de-DE", "en-US", "fr"]);
+        assert_eq!(locale_path(&root_str, "fr").unwrap(), root.join("fr.json"));
+        assert!(locale_path(&root_str, "es").is_err());
+    }
+
+    #[test]
+    fn test_find_references() {
+        let translations: HashMap<String, Value> = [
+            ("a", "Hello $t(user.name), $t(items, {\"count\": 2})"),
+            ("b", "@:common.save or @.lower:(common.cancel)."),
+            ("c", "Plain text with an @ sign and $t"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+        .collect();
+
+        let references = find_references(&translations, false);
+        assert_eq!(references["a"], ["user.name", "items"]);
+        assert_eq!(references["b"], ["common.save", "common.cancel"]);
+        assert!(!references.contains_key("c"));
+
+        let namespaced: HashMap<String, Value> =
+            [("billing:title", "$t(invoice.total) $t(common:save)")]
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                .collect();
+        assert_eq!(
+            find_references(&namespaced, true)["billing:title"],
+            ["billing:invoice.total", "common:save"]
+        );
     }
 }
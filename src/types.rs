@@ -0,0 +1,282 @@
+//! Values of TypeScript string-literal unions, enums and `as const` arrays declared in the
+//! project, used to narrow dynamically built keys (`` `status.${item.status}` ``) to the values
+//! the interpolated expression can take.
+//!
+//! Declarations are matched by name without a type checker: `item.status` takes the values of
+//! every `status: <type>` annotation in the project. Narrowing only happens when all of them
+//! resolve to known values, so an unknown type (`status: string`) keeps every key matching.
+
+use regex::Regex;
+use std::collections::HashMap;
+
+/// One member of a union: a string literal, `typeof NAME[number]`, or a (dotted) name
+const MEMBER: &str = r#"'[^'\n]*'|"[^"\n]*"|\(\s*typeof\s+[\w$]+\s*\)\s*\[\s*number\s*\]|typeof\s+[\w$]+\s*\[\s*number\s*\]|[\w$]+(?:\.[\w$]+)*"#;
+
+/// Type names that never narrow a value to known strings
+const OPEN_TYPES: &[&str] = &["string", "String", "any", "unknown", "number", "object"];
+
+/// Limit on alias chains (`type A = B`), which also stops cycles
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// What an annotation says about the values of the annotated name
+enum Evidence {
+    /// A type whose values are all known
+    Values(Vec<String>),
+    /// A type with values that are not known
+    Open,
+    /// Probably not a type at all (`{ status: 'open' }` or `{ status: item.status }` in an object)
+    None,
+}
+
+/// String values of the types declared in the scanned files
+pub struct TypeIndex {
+    /// `type Status = 'open' | 'closed'`, with the members as written
+    aliases: HashMap<String, Vec<String>>,
+    /// `enum Status { Open = 'open' }`; `None` when a member value is computed
+    enums: HashMap<String, Option<Vec<(String, String)>>>,
+    /// `const STATUSES = ['open', 'closed'] as const`
+    arrays: HashMap<String, Vec<String>>,
+    /// Members of every `name: type` annotation, by name
+    annotations: HashMap<String, Vec<Vec<String>>>,
+    alias: Regex,
+    enumeration: Regex,
+    enum_member: Regex,
+    array: Regex,
+    annotation: Regex,
+    member: Regex,
+}
+
+impl TypeIndex {
+    pub fn new() -> Self {
+        let union = format!(r"\|?\s*(?:{})(?:\s*\|\s*(?:{}))*", MEMBER, MEMBER);
+        TypeIndex {
+            aliases: HashMap::new(),
+            enums: HashMap::new(),
+            arrays: HashMap::new(),
+            annotations: HashMap::new(),
+            alias: Regex::new(&format!(
+                r"\btype\s+([\w$]+)\s*=\s*({})\s*(?:;|\n|$)",
+                union
+            ))
+            .unwrap(),
+            enumeration: Regex::new(r"\benum\s+([\w$]+)\s*\{([^{}]*)\}").unwrap(),
+            enum_member: Regex::new(
+                r#"^([\w$]+|'[^']*'|"[^"]*")\s*(?:=\s*(?:'([^']*)'|"([^"]*)"|(-?\d+)))?$"#,
+            )
+            .unwrap(),
+            array: Regex::new(
+                r"\b(?:const|let|var)\s+([\w$]+)\s*=\s*\[([^\[\]]*)\]\s*as\s+const\b",
+            )
+            .unwrap(),
+            annotation: Regex::new(&format!(r"([\w$]+)\s*\??\s*:\s*({})\s*[;,)=}}\n]", union))
+                .unwrap(),
+            member: Regex::new(MEMBER).unwrap(),
+        }
+    }
+
+    /// Record the declarations of a source file (with comments already stripped)
+    pub fn scan(&mut self, content: &str) {
+        for captures in self.alias.captures_iter(content) {
+            let members = self.members(&captures[2]);
+            self.aliases.insert(captures[1].to_string(), members);
+        }
+
+        for captures in self.enumeration.captures_iter(content) {
+            let members = self.enum_members(&captures[2]);
+            self.enums.insert(captures[1].to_string(), members);
+        }
+
+        for captures in self.array.captures_iter(content) {
+            let elements: Vec<&str> = captures[2]
+                .split(',')
+                .map(str::trim)
+                .filter(|element| !element.is_empty())
+                .collect();
+            let values: Option<Vec<String>> = elements.iter().map(|e| unquote(e)).collect();
+            if let Some(values) = values {
+                self.arrays.insert(captures[1].to_string(), values);
+            }
+        }
+
+        for captures in self.annotation.captures_iter(content) {
+            let members = self.members(&captures[2]);
+            self.annotations
+                .entry(captures[1].to_string())
+                .or_default()
+                .push(members);
+        }
+    }
+
+    /// Values an expression such as `item.status` or `Status.Open` can take, if known
+    pub fn values_of(&self, expression: &str) -> Option<Vec<String>> {
+        let expression = expression.replace("?.", ".").replace('!', "");
+        if !expression
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.')
+        {
+            return None;
+        }
+
+        // An enum member used directly
+        if let Some((name, member)) = expression.rsplit_once('.') {
+            if let Some(Some(members)) = self.enums.get(name) {
+                let value = members.iter().find(|(m, _)| m == member)?;
+                return Some(vec![value.1.clone()]);
+            }
+        }
+
+        let name = expression.rsplit('.').next().unwrap_or(&expression);
+        let mut values: Vec<String> = Vec::new();
+        for members in self.annotations.get(name)? {
+            match self.evidence(members) {
+                Evidence::Values(known) => values.extend(known),
+                Evidence::Open => return None,
+                Evidence::None => {}
+            }
+        }
+
+        values.sort();
+        values.dedup();
+        (!values.is_empty()).then_some(values)
+    }
+
+    fn evidence(&self, members: &[String]) -> Evidence {
+        if let [member] = members {
+            let is_type_name =
+                member.starts_with(|c: char| c.is_uppercase()) && !member.contains('.');
+            if OPEN_TYPES.contains(&member.as_str()) {
+                return Evidence::Open;
+            }
+            // A lone literal, enum member or lowercase name is more likely an object property value
+            if !is_type_name && !member.starts_with("typeof") && !member.starts_with('(') {
+                return Evidence::None;
+            }
+        }
+
+        let mut values = Vec::new();
+        for member in members {
+            match self.member_values(member, 0) {
+                Some(known) => values.extend(known),
+                None => return Evidence::Open,
+            }
+        }
+        Evidence::Values(values)
+    }
+
+    fn member_values(&self, member: &str, depth: usize) -> Option<Vec<String>> {
+        if let Some(value) = unquote(member) {
+            return Some(vec![value]);
+        }
+        if depth > MAX_ALIAS_DEPTH {
+            return None;
+        }
+
+        if member.contains("typeof") {
+            let name = member
+                .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .find(|part| !part.is_empty() && *part != "typeof" && *part != "number")?;
+            return self.arrays.get(name).cloned();
+        }
+
+        if let Some((name, member)) = member.split_once('.') {
+            let members = self.enums.get(name)?.as_ref()?;
+            let value = members.iter().find(|(m, _)| m == member)?;
+            return Some(vec![value.1.clone()]);
+        }
+
+        if let Some(members) = self.aliases.get(member) {
+            let mut values = Vec::new();
+            for member in members {
+                values.extend(self.member_values(member, depth + 1)?);
+            }
+            return Some(values);
+        }
+
+        let members = self.enums.get(member)?.as_ref()?;
+        Some(members.iter().map(|(_, value)| value.clone()).collect())
+    }
+
+    fn members(&self, union: &str) -> Vec<String> {
+        self.member
+            .find_iter(union)
+            .map(|m| m.as_str().to_string())
+            .collect()
+    }
+
+    /// Members with their values; numeric members without initializer count up from the last one
+    fn enum_members(&self, body: &str) -> Option<Vec<(String, String)>> {
+        let mut members = Vec::new();
+        let mut next = 0i64;
+        for member in body.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let captures = self.enum_member.captures(member)?;
+            let name = unquote(&captures[1]).unwrap_or_else(|| captures[1].to_string());
+            let value = match (captures.get(2).or(captures.get(3)), captures.get(4)) {
+                (Some(text), _) => text.as_str().to_string(),
+                (None, Some(number)) => {
+                    next = number.as_str().parse::<i64>().ok()? + 1;
+                    number.as_str().to_string()
+                }
+                (None, None) => {
+                    next += 1;
+                    (next - 1).to_string()
+                }
+            };
+            members.push((name, value));
+        }
+        Some(members)
+    }
+}
+
+impl Default for TypeIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Contents of a single- or double-quoted string literal
+fn unquote(text: &str) -> Option<String> {
+    let quoted = text.len() >= 2
+        && ((text.starts_with('\'') && text.ends_with('\''))
+            || (text.starts_with('"') && text.ends_with('"')));
+    quoted.then(|| text[1..text.len() - 1].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_of() {
+        let mut index = TypeIndex::new();
+        index.scan(
+            r#"
+type Status = 'open' | 'closed';
+type Review =
+  | Status
+  | "pending";
+enum Color { Red = 'red', Blue = "blue" }
+enum Level { Low, High }
+const SIZES = ['s', 'm'] as const;
+interface Item { status: Status; review?: Review; size: typeof SIZES[number]; level: Level; name: string }
+const item = { status: 'open', name: user.name };
+function paint(color: Color) {}
+"#,
+        );
+
+        let values = |expression: &str| index.values_of(expression);
+        let strings = |values: &[&str]| Some(values.iter().map(|v| v.to_string()).collect());
+
+        assert_eq!(values("item.status"), strings(&["closed", "open"]));
+        assert_eq!(
+            values("props.item?.review"),
+            strings(&["closed", "open", "pending"])
+        );
+        assert_eq!(values("color"), strings(&["blue", "red"]));
+        assert_eq!(values("Color.Blue"), strings(&["blue"]));
+        assert_eq!(values("item.size"), strings(&["m", "s"]));
+        assert_eq!(values("item.level"), strings(&["0", "1"]));
+        assert_eq!(values("item.name"), None);
+        assert_eq!(values("unknown"), None);
+        assert_eq!(values("labels[item.status]"), None);
+    }
+}
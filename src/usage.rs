@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+/// How a usage was matched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// The key itself appears in the source
+    Exact,
+    /// The key is covered by a dynamically built key (`` `status.${value}` ``)
+    Dynamic,
+    /// The key is named by a `hunt-keep:` / `i18n-keys:` comment
+    Annotation,
+}
+
+impl MatchKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchKind::Exact => "exact",
+            MatchKind::Dynamic => "dynamic",
+            MatchKind::Annotation => "annotation",
+        }
+    }
+}
+
+/// A place in a source file where a key is used (1-based line and column)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub kind: MatchKind,
+}
+
+/// Index of used keys and where they are used
+#[derive(Debug, Default)]
+pub struct UsageIndex {
+    locations: HashMap<String, Vec<UsageLocation>>,
+    /// Keys used because of the keep-list, with the pattern covering them
+    kept: HashMap<String, String>,
+    /// Keys used because the value of another used key references them, with that key
+    referenced: HashMap<String, String>,
+}
+
+impl UsageIndex {
+    /// Record a usage of `key`
+    pub fn record(&mut self, key: &str, location: UsageLocation) {
+        match self.locations.get_mut(key) {
+            Some(locations) => locations.push(location),
+            None => {
+                self.locations.insert(key.to_string(), vec![location]);
+            }
+        }
+    }
+
+    /// Mark `key` as used because the keep-list `pattern` covers it
+    pub fn keep(&mut self, key: &str, pattern: &str) {
+        self.kept.insert(key.to_string(), pattern.to_string());
+    }
+
+    /// Mark `key` as used because the value of the used key `from` references it
+    pub fn reference(&mut self, key: &str, from: &str) {
+        self.referenced.insert(key.to_string(), from.to_string());
+    }
+
+    /// Stop tracking a key and return where it was used
+    pub fn remove(&mut self, key: &str) -> Vec<UsageLocation> {
+        self.kept.remove(key);
+        self.referenced.remove(key);
+        self.locations.remove(key).unwrap_or_default()
+    }
+
+    /// Whether the key is used anywhere or kept
+    pub fn contains(&self, key: &str) -> bool {
+        self.locations.contains_key(key)
+            || self.kept.contains_key(key)
+            || self.referenced.contains_key(key)
+    }
+
+    /// Keep-list pattern covering the key
+    pub fn kept_by(&self, key: &str) -> Option<&str> {
+        self.kept.get(key).map(|pattern| pattern.as_str())
+    }
+
+    /// Used key whose value references the key
+    pub fn referenced_by(&self, key: &str) -> Option<&str> {
+        self.referenced.get(key).map(|from| from.as_str())
+    }
+
+    /// Locations where the key is used, sorted by file and position
+    pub fn locations(&self, key: &str) -> Vec<&UsageLocation> {
+        let mut locations: Vec<&UsageLocation> = self
+            .locations
+            .get(key)
+            .map(|locations| locations.iter().collect())
+            .unwrap_or_default();
+        locations.sort_by(|a, b| (&a.file, a.line, a.column).cmp(&(&b.file, b.line, b.column)));
+        locations
+    }
+
+    /// All used keys, kept and referenced keys included
+    pub fn used_keys(&self) -> HashSet<String> {
+        self.locations
+            .keys()
+            .chain(self.kept.keys())
+            .chain(self.referenced.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Add every usage of another index
+    pub fn merge(&mut self, other: UsageIndex) {
+        for (key, locations) in other.locations {
+            self.locations.entry(key).or_default().extend(locations);
+        }
+        self.kept.extend(other.kept);
+        self.referenced.extend(other.referenced);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(file: &str, line: usize, kind: MatchKind) -> UsageLocation {
+        UsageLocation {
+            file: file.to_string(),
+            line,
+            column: 1,
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_record_and_merge() {
+        let mut index = UsageIndex::default();
+        index.record("app.title", location("b.tsx", 3, MatchKind::Exact));
+
+        let mut other = UsageIndex::default();
+        other.record("app.title", location("a.tsx", 7, MatchKind::Dynamic));
+        other.record("app.name", location("a.tsx", 1, MatchKind::Exact));
+        index.merge(other);
+
+        assert!(index.contains("app.name"));
+        assert!(!index.contains("app"));
+        assert_eq!(index.used_keys().len(), 2);
+
+        let locations = index.locations("app.title");
+        assert_eq!(locations.len(), 2);
+        assert_eq!(locations[0].file, "a.tsx");
+        assert_eq!(locations[0].kind, MatchKind::Dynamic);
+        assert!(index.locations("missing").is_empty());
+
+        index.keep("errors.network", "errors.*");
+        assert!(index.contains("errors.network"));
+        assert_eq!(index.kept_by("errors.network"), Some("errors.*"));
+        assert_eq!(index.used_keys().len(), 3);
+
+        index.reference("common.save", "app.title");
+        assert!(index.contains("common.save"));
+        assert_eq!(index.referenced_by("common.save"), Some("app.title"));
+        assert_eq!(index.used_keys().len(), 4);
+    }
+}